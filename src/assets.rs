@@ -0,0 +1,187 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use image::{ImageFormat, RgbaImage, imageops::FilterType};
+
+use crate::{file_ops::create_backup, game_folder::GameFolderLayout};
+
+/// Images shown on the XMB next to the title.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum::Display, strum::EnumIter)]
+pub enum Asset {
+  #[strum(serialize = "ICON0.PNG")]
+  Icon0,
+  #[strum(serialize = "PIC0.PNG")]
+  Pic0,
+  #[strum(serialize = "PIC1.PNG")]
+  Pic1,
+}
+
+impl Asset {
+  /// Dimensions the console expects for the asset in the folder layout. `None` when they are
+  /// not known.
+  pub fn expected_size(&self, layout: GameFolderLayout) -> Option<(u32, u32)> {
+    if let GameFolderLayout::SceSys = layout {
+      return None;
+    }
+
+    let size = match self {
+      Asset::Icon0 => (320, 176),
+      Asset::Pic0 => (1000, 560),
+      Asset::Pic1 => (1920, 1080),
+    };
+    Some(size)
+  }
+
+  /// Whether the title is shown without a warning when the asset is missing.
+  pub fn is_optional(&self) -> bool {
+    !matches!(self, Asset::Icon0)
+  }
+}
+
+/// How an image of different proportions is converted to the size of the asset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumIter)]
+pub enum ResizeMode {
+  /// Scales the whole image to fit and fills the rest with transparency.
+  Fit,
+  /// Scales the image to cover the whole asset and crops what does not fit.
+  Fill,
+  /// Scales the image to the size of the asset, ignoring its proportions.
+  Stretch,
+}
+
+pub fn resize_image(image: &RgbaImage, (width, height): (u32, u32), mode: ResizeMode) -> RgbaImage {
+  match mode {
+    ResizeMode::Fit => {
+      let resized = image::DynamicImage::ImageRgba8(image.clone())
+        .resize(width, height, FilterType::Lanczos3)
+        .to_rgba8();
+      let mut canvas = RgbaImage::new(width, height);
+      image::imageops::overlay(
+        &mut canvas,
+        &resized,
+        ((width - resized.width()) / 2) as i64,
+        ((height - resized.height()) / 2) as i64,
+      );
+      canvas
+    }
+    ResizeMode::Fill => image::DynamicImage::ImageRgba8(image.clone())
+      .resize_to_fill(width, height, FilterType::Lanczos3)
+      .to_rgba8(),
+    ResizeMode::Stretch => image::imageops::resize(image, width, height, FilterType::Lanczos3),
+  }
+}
+
+/// Writes the image as PNG under the file name of the asset, backing up the previous asset unless
+/// its backup already exists. Existing asset is overwritten regardless of the case of its file
+/// name.
+pub fn save_asset<T>(dir: T, asset: Asset, image: &RgbaImage) -> Result<PathBuf, String>
+where
+  T: AsRef<Path>,
+{
+  let dir = dir.as_ref();
+  let existing_path = fs::read_dir(dir)
+    .map_err(|err| format!("could not read directory {}: {err}", dir.display()))?
+    .filter_map(|entry| entry.ok())
+    .find(|entry| {
+      entry
+        .file_name()
+        .to_string_lossy()
+        .eq_ignore_ascii_case(&asset.to_string())
+    })
+    .map(|entry| entry.path());
+
+  let path = match existing_path {
+    Some(path) => {
+      create_backup(&path)?;
+      path
+    }
+    None => dir.join(asset.to_string()),
+  };
+
+  image
+    .save_with_format(&path, ImageFormat::Png)
+    .map_err(|err| format!("could not save {}: {err}", path.display()))?;
+  Ok(path)
+}
+
+pub fn load_image<T>(path: T) -> Result<RgbaImage, String>
+where
+  T: AsRef<Path>,
+{
+  image::open(&path)
+    .map(|image| image.to_rgba8())
+    .map_err(|err| {
+      format!(
+        "could not load image {}: {err}",
+        path.as_ref().to_string_lossy()
+      )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use image::Rgba;
+
+  use super::*;
+  use crate::file_ops::backup_path;
+
+  /// Empty folder in the system temporary directory, unique for the test.
+  fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sfo-tool-assets-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn wide_image() -> RgbaImage {
+    RgbaImage::from_pixel(40, 20, Rgba([255, 0, 0, 255]))
+  }
+
+  #[test]
+  fn resize_fit_keeps_proportions_on_transparent_canvas() {
+    let resized = resize_image(&wide_image(), (32, 32), ResizeMode::Fit);
+
+    assert_eq!(resized.dimensions(), (32, 32));
+    assert_eq!(resized.get_pixel(16, 0)[3], 0);
+    assert_eq!(resized.get_pixel(16, 16)[3], 255);
+  }
+
+  #[test]
+  fn resize_fill_and_stretch_cover_whole_asset() {
+    for mode in [ResizeMode::Fill, ResizeMode::Stretch] {
+      let resized = resize_image(&wide_image(), (32, 32), mode);
+
+      assert_eq!(resized.dimensions(), (32, 32), "{mode}");
+      assert!(resized.pixels().all(|pixel| pixel[3] == 255), "{mode}");
+    }
+  }
+
+  #[test]
+  fn save_asset_writes_png() {
+    let dir = test_dir("new");
+    let path = save_asset(&dir, Asset::Icon0, &wide_image()).unwrap();
+
+    assert_eq!(path, dir.join("ICON0.PNG"));
+    assert_eq!(load_image(&path).unwrap().dimensions(), (40, 20));
+    assert!(!backup_path(&path).exists());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn save_asset_overwrites_existing_asset_after_backup() {
+    let dir = test_dir("existing");
+    let existing_path = dir.join("icon0.png");
+    fs::write(&existing_path, b"previous").unwrap();
+
+    let path = save_asset(&dir, Asset::Icon0, &wide_image()).unwrap();
+    assert_eq!(path, existing_path);
+    assert_eq!(load_image(&path).unwrap().dimensions(), (40, 20));
+    assert_eq!(fs::read(backup_path(&path)).unwrap(), b"previous");
+
+    save_asset(&dir, Asset::Icon0, &RgbaImage::new(8, 8)).unwrap();
+    assert_eq!(fs::read(backup_path(&path)).unwrap(), b"previous");
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
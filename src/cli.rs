@@ -0,0 +1,267 @@
+use std::{
+  fs::{self, OpenOptions},
+  io::{BufReader, BufWriter},
+  path::{Path, PathBuf},
+};
+
+use clap::Subcommand;
+
+use crate::{
+  file_ops::{
+    is_sfx_path, load_icon_file, load_manifest_file, load_sfo_file, save_pbp_file, save_sfo_file,
+    save_sfx_file,
+  },
+  game_folder::GameFolder,
+  library::{LibraryEntry, export_library_csv, plan_library_csv_import, scan_library},
+  report::{ReportFile, ReportFormat, render_report},
+  sfo::{Sfo, keys::Keys, validation::validate},
+};
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Work with PARAM.SFO embedded in a PSP EBOOT.PBP file
+  Pbp {
+    #[command(subcommand)]
+    command: PbpCommand,
+  },
+  /// Convert between binary PARAM.SFO and param.sfx XML
+  Convert {
+    #[arg(long, help = "Path to a .sfo, .pbp, .iso or .sfx file")]
+    input: PathBuf,
+    #[arg(
+      long,
+      help = "Path of the written file, written as param.sfx XML when it has the .sfx extension and as .sfo otherwise"
+    )]
+    output: PathBuf,
+  },
+  /// Export and edit values of all files in a library folder through csv
+  Library {
+    #[command(subcommand)]
+    command: LibraryCommand,
+  },
+  /// Write header, index table and values of a file or a library as a report
+  Report {
+    #[arg(
+      long,
+      help = "Path to a .sfo, .pbp, .iso or .sfx file, or a game folder"
+    )]
+    input: PathBuf,
+    #[arg(
+      long,
+      help = "Scan the input folder recursively and report every file found in it"
+    )]
+    library: bool,
+    #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+    format: ReportFormat,
+    #[arg(long, help = "Path of the written report")]
+    output: PathBuf,
+  },
+  /// Build PARAM.SFO from a TOML manifest
+  Build {
+    #[arg(long, help = "Path to a .toml manifest")]
+    manifest: PathBuf,
+    #[arg(
+      long,
+      help = "Path of the written file, written as param.sfx XML when it has the .sfx extension and as .sfo otherwise"
+    )]
+    output: PathBuf,
+  },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PbpCommand {
+  /// Write PARAM.SFO of the PBP file to a .sfo file
+  Extract {
+    #[arg(long, help = "Path to a .pbp file")]
+    pbp: PathBuf,
+    #[arg(long, help = "Path of the extracted .sfo file")]
+    output: PathBuf,
+  },
+  /// Replace PARAM.SFO of the PBP file with a .sfo file
+  Inject {
+    #[arg(long, help = "Path to a .pbp file")]
+    pbp: PathBuf,
+    #[arg(long, help = "Path to a .sfo file")]
+    sfo: PathBuf,
+    #[arg(
+      long,
+      required = false,
+      help = "Path of the written .pbp file, the input .pbp file is overwritten when not provided"
+    )]
+    output: Option<PathBuf>,
+  },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LibraryCommand {
+  /// Write the path and values of the keys of every file in the folder to a .csv file
+  Export {
+    #[arg(
+      long,
+      help = "Path to a folder scanned recursively for .sfo, .pbp, .iso and .sfx files"
+    )]
+    root: PathBuf,
+    #[arg(
+      long,
+      value_delimiter = ',',
+      required = true,
+      help = "Comma-separated keys written as columns, e.g. TITLE_ID,TITLE,APP_VER"
+    )]
+    keys: Vec<String>,
+    #[arg(long, help = "Path of the written .csv file")]
+    output: PathBuf,
+  },
+  /// Show changes of an edited .csv file, and apply them to the referenced files with --apply
+  Import {
+    #[arg(long, help = "Path to a .csv file written by library export")]
+    csv: PathBuf,
+    #[arg(
+      long,
+      help = "Write the changes, backing up every changed file - only a preview is shown without it"
+    )]
+    apply: bool,
+  },
+}
+
+pub fn run(command: Command) -> Result<(), String> {
+  match command {
+    Command::Pbp { command } => run_pbp(command),
+    Command::Convert { input, output } => {
+      let sfo = load_sfo_file(&input)?;
+      save_output(&output, &sfo)
+    }
+    Command::Library { command } => run_library(command),
+    Command::Report {
+      input,
+      library,
+      format,
+      output,
+    } => run_report(&input, library, format, &output),
+    Command::Build { manifest, output } => {
+      let sfo = load_manifest_file(&manifest)?;
+      for issue in validate(&sfo) {
+        eprintln!("{issue}");
+      }
+      save_output(&output, &sfo)
+    }
+  }
+}
+
+fn run_library(command: LibraryCommand) -> Result<(), String> {
+  match command {
+    LibraryCommand::Export { root, keys, output } => {
+      let (entries, issues) = scan_library(&root);
+      for issue in issues {
+        eprintln!("skipped {issue}");
+      }
+
+      let keys: Vec<Keys> = keys
+        .iter()
+        .map(|name| name.parse().unwrap_or_else(|_| Keys::Unknown(name.clone())))
+        .collect();
+      let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&output)
+        .map_err(|err| format!("could not create file {}: {err}", output.display()))?;
+      export_library_csv(&entries, &keys, &mut BufWriter::new(file))?;
+      println!("exported {} files to {}", entries.len(), output.display());
+      Ok(())
+    }
+    LibraryCommand::Import { csv, apply } => {
+      let file = OpenOptions::new()
+        .read(true)
+        .open(&csv)
+        .map_err(|err| format!("could not load file {}: {err}", csv.display()))?;
+      let plan = plan_library_csv_import(&mut BufReader::new(file))?;
+      for issue in &plan.issues {
+        eprintln!("skipped {issue}");
+      }
+      for file in &plan.files {
+        for edit in &file.edits {
+          println!(
+            "{}: {} \"{}\" -> \"{}\"",
+            file.path.display(),
+            edit.key,
+            edit.old,
+            edit.new
+          );
+        }
+      }
+
+      if !apply {
+        println!(
+          "{} changes in {} files, run with --apply to write them",
+          plan.edits_count(),
+          plan.files.len()
+        );
+        return Ok(());
+      }
+
+      let (saved, errors) = plan.apply();
+      println!("saved {} files", saved.len());
+      match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors.join("\n")),
+      }
+    }
+  }
+}
+
+fn run_report(
+  input: &Path,
+  library: bool,
+  format: ReportFormat,
+  output: &Path,
+) -> Result<(), String> {
+  let entries = match library {
+    true => {
+      let (entries, issues) = scan_library(input);
+      for issue in issues {
+        eprintln!("skipped {issue}");
+      }
+      entries
+    }
+    false => {
+      let path = match input.is_dir() {
+        true => GameFolder::open(input)?.sfo_path,
+        false => input.to_path_buf(),
+      };
+      let sfo = load_sfo_file(&path)?;
+      vec![LibraryEntry { path, sfo }]
+    }
+  };
+
+  let files: Vec<ReportFile> = entries
+    .iter()
+    .map(|entry| ReportFile {
+      path: Some(&entry.path),
+      sfo: &entry.sfo,
+      icon: load_icon_file(&entry.path),
+    })
+    .collect();
+  fs::write(output, render_report(&files, format))
+    .map_err(|err| format!("could not save report {}: {err}", output.display()))
+}
+
+fn save_output(output: &Path, sfo: &Sfo) -> Result<(), String> {
+  match is_sfx_path(output) {
+    true => save_sfx_file(output, sfo),
+    false => save_sfo_file(output, sfo),
+  }
+}
+
+fn run_pbp(command: PbpCommand) -> Result<(), String> {
+  match command {
+    PbpCommand::Extract { pbp, output } => {
+      let sfo = load_sfo_file(&pbp)?;
+      save_sfo_file(&output, &sfo)
+    }
+    PbpCommand::Inject { pbp, sfo, output } => {
+      let sfo = load_sfo_file(&sfo)?;
+      let output = output.unwrap_or_else(|| pbp.clone());
+      save_pbp_file(&pbp, &output, &sfo)
+    }
+  }
+}
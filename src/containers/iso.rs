@@ -0,0 +1,452 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+
+pub const PS3_PARAM_SFO_PATH: &str = "PS3_GAME/PARAM.SFO";
+pub const PS3_ICON0_PATH: &str = "PS3_GAME/ICON0.PNG";
+
+const SECTOR_SIZE: u64 = 2048;
+const PRIMARY_VOLUME_DESCRIPTOR_SECTOR: u64 = 16;
+const PRIMARY_VOLUME_DESCRIPTOR_TYPE: u8 = 1;
+const SUPPLEMENTARY_VOLUME_DESCRIPTOR_TYPE: u8 = 2;
+const VOLUME_DESCRIPTOR_SET_TERMINATOR_TYPE: u8 = 255;
+/// Offset of the escape sequences telling a Joliet descriptor apart from other supplementary ones.
+const ESCAPE_SEQUENCES_OFFSET: usize = 88;
+const JOLIET_ESCAPE_SEQUENCES: [&[u8; 3]; 3] = [b"%/@", b"%/C", b"%/E"];
+const STANDARD_IDENTIFIER: &[u8; 5] = b"CD001";
+const ROOT_DIRECTORY_RECORD_OFFSET: u64 = 156;
+const DIRECTORY_RECORD_HEADER_SIZE: usize = 33;
+const DIRECTORY_FLAG: u8 = 0x02;
+/// Offsets of the both-endian data length within a directory record.
+const DATA_LEN_LE_OFFSET: u64 = 10;
+const DATA_LEN_BE_OFFSET: u64 = 14;
+
+/// Location of a file within an ISO 9660 image.
+#[derive(Clone, Debug)]
+pub struct IsoFile {
+  /// Offsets of the directory records describing the file - one in the primary volume and one in
+  /// each supplementary volume, such as Joliet, listing it.
+  record_offsets: Vec<u64>,
+  pub data_offset: u64,
+  pub data_len: u32,
+}
+
+impl IsoFile {
+  /// Size of the sectors allocated for the file - the file cannot grow past it in place.
+  pub fn allocated_len(&self) -> u64 {
+    (self.data_len as u64).div_ceil(SECTOR_SIZE) * SECTOR_SIZE
+  }
+}
+
+struct DirectoryRecord {
+  offset: u64,
+  len: u8,
+  extent: u32,
+  data_len: u32,
+  is_dir: bool,
+  name: String,
+}
+
+/// Finds the file under a `/`-separated path, matching names case-insensitively and ignoring
+/// version suffixes such as `;1`. Supplementary volumes listing the file must point to the same
+/// data as the primary volume, so that writing the file keeps all of them consistent.
+pub fn find_file<T>(reader: &mut T, path: &str) -> Result<IsoFile, String>
+where
+  T: Read + Seek,
+{
+  let descriptor = read_volume_descriptor(reader, PRIMARY_VOLUME_DESCRIPTOR_SECTOR)?;
+  if descriptor[0] != PRIMARY_VOLUME_DESCRIPTOR_TYPE || &descriptor[1..6] != STANDARD_IDENTIFIER {
+    return Err(String::from(
+      "image does not start with an ISO 9660 primary volume descriptor",
+    ));
+  }
+
+  let record = find_record(reader, PRIMARY_VOLUME_DESCRIPTOR_SECTOR, path, false)?
+    .ok_or_else(|| format!("could not find {path} in the image"))?;
+  let mut record_offsets = vec![record.offset];
+
+  let mut sector = PRIMARY_VOLUME_DESCRIPTOR_SECTOR + 1;
+  loop {
+    let descriptor = read_volume_descriptor(reader, sector)?;
+    if &descriptor[1..6] != STANDARD_IDENTIFIER
+      || descriptor[0] == VOLUME_DESCRIPTOR_SET_TERMINATOR_TYPE
+    {
+      break;
+    }
+
+    if descriptor[0] == SUPPLEMENTARY_VOLUME_DESCRIPTOR_TYPE {
+      let escape_sequences = &descriptor[ESCAPE_SEQUENCES_OFFSET..ESCAPE_SEQUENCES_OFFSET + 3];
+      let joliet = JOLIET_ESCAPE_SEQUENCES
+        .iter()
+        .any(|sequence| escape_sequences == sequence.as_slice());
+      if let Some(supplementary) = find_record(reader, sector, path, joliet)? {
+        if supplementary.extent != record.extent || supplementary.data_len != record.data_len {
+          return Err(format!(
+            "{path} in the supplementary volume at sector {sector} does not match the primary volume"
+          ));
+        }
+        record_offsets.push(supplementary.offset);
+      }
+    }
+    sector += 1;
+  }
+
+  Ok(IsoFile {
+    record_offsets,
+    data_offset: record.extent as u64 * SECTOR_SIZE,
+    data_len: record.data_len,
+  })
+}
+
+pub fn read_file<T>(reader: &mut T, file: &IsoFile) -> Result<Vec<u8>, String>
+where
+  T: Read + Seek,
+{
+  let mut data = vec![0; file.data_len as usize];
+  reader
+    .seek(SeekFrom::Start(file.data_offset))
+    .and_then(|_| reader.read_exact(&mut data))
+    .map_err(|err| format!("cannot read file data: {err}"))?;
+  Ok(data)
+}
+
+/// Overwrites the file in place and updates its length in every directory record describing it.
+/// Fails when the data does not fit in the sectors allocated for the file.
+pub fn write_file<T>(writer: &mut T, file: &IsoFile, data: &[u8]) -> Result<(), String>
+where
+  T: Write + Seek,
+{
+  if data.len() as u64 > file.allocated_len() {
+    return Err(format!(
+      "file takes {} bytes which exceeds {} bytes allocated for it in the image",
+      data.len(),
+      file.allocated_len()
+    ));
+  }
+
+  let mut sectors = data.to_vec();
+  sectors.resize(file.allocated_len() as usize, 0);
+  let data_len = data.len() as u32;
+  let write = |writer: &mut T| -> std::io::Result<()> {
+    writer.seek(SeekFrom::Start(file.data_offset))?;
+    writer.write_all(&sectors)?;
+    for record_offset in &file.record_offsets {
+      writer.seek(SeekFrom::Start(record_offset + DATA_LEN_LE_OFFSET))?;
+      writer.write_all(&data_len.to_le_bytes())?;
+      writer.seek(SeekFrom::Start(record_offset + DATA_LEN_BE_OFFSET))?;
+      writer.write_all(&data_len.to_be_bytes())?;
+    }
+    writer.flush()
+  };
+  write(writer).map_err(|err| format!("cannot write file data: {err}"))
+}
+
+fn read_volume_descriptor<T>(reader: &mut T, sector: u64) -> Result<[u8; 128], String>
+where
+  T: Read + Seek,
+{
+  let mut descriptor = [0u8; 128];
+  reader
+    .seek(SeekFrom::Start(sector * SECTOR_SIZE))
+    .and_then(|_| reader.read_exact(&mut descriptor))
+    .map_err(|err| format!("cannot read volume descriptor: {err}"))?;
+  Ok(descriptor)
+}
+
+/// Walks the directory tree of the volume described at the sector. Returns `None` when the volume
+/// does not list the file.
+fn find_record<T>(
+  reader: &mut T,
+  descriptor_sector: u64,
+  path: &str,
+  joliet: bool,
+) -> Result<Option<DirectoryRecord>, String>
+where
+  T: Read + Seek,
+{
+  let mut record = read_directory_record(
+    reader,
+    descriptor_sector * SECTOR_SIZE + ROOT_DIRECTORY_RECORD_OFFSET,
+    joliet,
+  )?
+  .ok_or_else(|| String::from("image does not have a root directory"))?;
+
+  for name in path.split('/').filter(|name| !name.is_empty()) {
+    if !record.is_dir {
+      return Err(format!("{} is not a directory", record.name));
+    }
+
+    match read_directory(reader, &record, joliet)?
+      .into_iter()
+      .find(|child| child.name.eq_ignore_ascii_case(name))
+    {
+      Some(child) => record = child,
+      None => return Ok(None),
+    }
+  }
+
+  if record.is_dir {
+    return Err(format!("{path} is a directory"));
+  }
+
+  Ok(Some(record))
+}
+
+fn read_directory<T>(
+  reader: &mut T,
+  dir: &DirectoryRecord,
+  joliet: bool,
+) -> Result<Vec<DirectoryRecord>, String>
+where
+  T: Read + Seek,
+{
+  let start = dir.extent as u64 * SECTOR_SIZE;
+  let end = start + dir.data_len as u64;
+  let mut records = Vec::new();
+  let mut offset = start;
+  while offset < end {
+    match read_directory_record(reader, offset, joliet)? {
+      Some(record) => {
+        offset += record.len as u64;
+        // The first two records point to the directory itself and its parent.
+        if !record.name.is_empty() {
+          records.push(record);
+        }
+      }
+      // Records do not cross sector boundaries, the rest of the sector is zeroed.
+      None => offset = (offset / SECTOR_SIZE + 1) * SECTOR_SIZE,
+    }
+  }
+
+  Ok(records)
+}
+
+/// Reads the record at the offset. Joliet names are stored as UTF-16BE.
+fn read_directory_record<T>(
+  reader: &mut T,
+  offset: u64,
+  joliet: bool,
+) -> Result<Option<DirectoryRecord>, String>
+where
+  T: Read + Seek,
+{
+  let mut header = [0u8; DIRECTORY_RECORD_HEADER_SIZE];
+  reader
+    .seek(SeekFrom::Start(offset))
+    .and_then(|_| reader.read_exact(&mut header))
+    .map_err(|err| format!("cannot read directory record: {err}"))?;
+  if header[0] == 0 {
+    return Ok(None);
+  }
+
+  let mut name = vec![0u8; header[32] as usize];
+  reader
+    .read_exact(&mut name)
+    .map_err(|err| format!("cannot read directory record name: {err}"))?;
+  let name = match name.as_slice() {
+    [0] | [1] => String::new(),
+    name => {
+      let name = match joliet {
+        true => String::from_utf16_lossy(
+          &name
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>(),
+        ),
+        false => String::from_utf8_lossy(name).into_owned(),
+      };
+      let name = name.split(';').next().unwrap_or_default();
+      name.strip_suffix('.').unwrap_or(name).to_string()
+    }
+  };
+
+  Ok(Some(DirectoryRecord {
+    offset,
+    len: header[0],
+    extent: u32::from_le_bytes([header[2], header[3], header[4], header[5]]),
+    data_len: u32::from_le_bytes([header[10], header[11], header[12], header[13]]),
+    is_dir: header[25] & DIRECTORY_FLAG != 0,
+    name,
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use super::*;
+
+  const PRIMARY_ROOT_SECTOR: u32 = 19;
+  const JOLIET_ROOT_SECTOR: u32 = 21;
+  const FILE_SECTOR: u32 = 23;
+  const IMAGE_SECTORS: u32 = 24;
+
+  fn record(extent: u32, data_len: u32, is_dir: bool, name: &[u8]) -> Vec<u8> {
+    let len = DIRECTORY_RECORD_HEADER_SIZE + name.len() + (name.len() + 1) % 2;
+    let mut record = vec![0u8; len];
+    record[0] = len as u8;
+    record[2..6].copy_from_slice(&extent.to_le_bytes());
+    record[6..10].copy_from_slice(&extent.to_be_bytes());
+    record[10..14].copy_from_slice(&data_len.to_le_bytes());
+    record[14..18].copy_from_slice(&data_len.to_be_bytes());
+    record[25] = if is_dir { DIRECTORY_FLAG } else { 0 };
+    record[32] = name.len() as u8;
+    record[33..33 + name.len()].copy_from_slice(name);
+    record
+  }
+
+  fn utf16(name: &str) -> Vec<u8> {
+    name.encode_utf16().flat_map(u16::to_be_bytes).collect()
+  }
+
+  fn write_at(image: &mut [u8], offset: u64, bytes: &[u8]) {
+    let offset = offset as usize;
+    image[offset..offset + bytes.len()].copy_from_slice(bytes);
+  }
+
+  fn write_directory(image: &mut [u8], sector: u32, parent: u32, children: &[Vec<u8>]) {
+    let mut offset = sector as u64 * SECTOR_SIZE;
+    let own = record(sector, SECTOR_SIZE as u32, true, &[0]);
+    let parent = record(parent, SECTOR_SIZE as u32, true, &[1]);
+    for record in [&own, &parent].into_iter().chain(children) {
+      write_at(image, offset, record);
+      offset += record.len() as u64;
+    }
+  }
+
+  /// Image with PS3_GAME/PARAM.SFO listed by a primary and a Joliet volume. The Joliet entry
+  /// points at `joliet_extent`.
+  fn image(data: &[u8], joliet_extent: u32) -> Vec<u8> {
+    let mut image = vec![0u8; (IMAGE_SECTORS as u64 * SECTOR_SIZE) as usize];
+    let data_len = data.len() as u32;
+
+    let descriptor = |kind: u8| {
+      let mut descriptor = vec![kind];
+      descriptor.extend(STANDARD_IDENTIFIER);
+      descriptor.push(1);
+      descriptor
+    };
+    let primary = PRIMARY_VOLUME_DESCRIPTOR_SECTOR * SECTOR_SIZE;
+    write_at(
+      &mut image,
+      primary,
+      &descriptor(PRIMARY_VOLUME_DESCRIPTOR_TYPE),
+    );
+    write_at(
+      &mut image,
+      primary + ROOT_DIRECTORY_RECORD_OFFSET,
+      &record(PRIMARY_ROOT_SECTOR, SECTOR_SIZE as u32, true, &[0]),
+    );
+    let joliet = primary + SECTOR_SIZE;
+    write_at(
+      &mut image,
+      joliet,
+      &descriptor(SUPPLEMENTARY_VOLUME_DESCRIPTOR_TYPE),
+    );
+    write_at(&mut image, joliet + ESCAPE_SEQUENCES_OFFSET as u64, b"%/E");
+    write_at(
+      &mut image,
+      joliet + ROOT_DIRECTORY_RECORD_OFFSET,
+      &record(JOLIET_ROOT_SECTOR, SECTOR_SIZE as u32, true, &[0]),
+    );
+    write_at(
+      &mut image,
+      joliet + SECTOR_SIZE,
+      &descriptor(VOLUME_DESCRIPTOR_SET_TERMINATOR_TYPE),
+    );
+
+    let root = PRIMARY_ROOT_SECTOR;
+    write_directory(
+      &mut image,
+      root,
+      root,
+      &[record(root + 1, SECTOR_SIZE as u32, true, b"PS3_GAME")],
+    );
+    write_directory(
+      &mut image,
+      root + 1,
+      root,
+      &[record(FILE_SECTOR, data_len, false, b"PARAM.SFO;1")],
+    );
+    let root = JOLIET_ROOT_SECTOR;
+    write_directory(
+      &mut image,
+      root,
+      root,
+      &[record(
+        root + 1,
+        SECTOR_SIZE as u32,
+        true,
+        &utf16("PS3_GAME"),
+      )],
+    );
+    write_directory(
+      &mut image,
+      root + 1,
+      root,
+      &[record(
+        joliet_extent,
+        data_len,
+        false,
+        &utf16("PARAM.SFO;1"),
+      )],
+    );
+
+    write_at(&mut image, FILE_SECTOR as u64 * SECTOR_SIZE, data);
+    image
+  }
+
+  fn joliet_data_len(image: &[u8]) -> u32 {
+    let mut reader = Cursor::new(image);
+    let record = find_record(
+      &mut reader,
+      PRIMARY_VOLUME_DESCRIPTOR_SECTOR + 1,
+      "PS3_GAME/PARAM.SFO",
+      true,
+    )
+    .unwrap()
+    .unwrap();
+    record.data_len
+  }
+
+  #[test]
+  fn find_file_reads_the_file_listed_by_both_volumes() {
+    let image = image(b"PSF data", FILE_SECTOR);
+    let mut reader = Cursor::new(&image);
+
+    let file = find_file(&mut reader, "ps3_game/param.sfo").unwrap();
+
+    assert_eq!(file.record_offsets.len(), 2);
+    assert_eq!(file.allocated_len(), SECTOR_SIZE);
+    assert_eq!(read_file(&mut reader, &file).unwrap(), b"PSF data");
+  }
+
+  #[test]
+  fn write_file_updates_length_in_every_volume() {
+    let mut image = Cursor::new(image(b"PSF data", FILE_SECTOR));
+    let file = find_file(&mut image, PS3_PARAM_SFO_PATH).unwrap();
+
+    write_file(&mut image, &file, b"longer PSF data").unwrap();
+
+    let file = find_file(&mut image, PS3_PARAM_SFO_PATH).unwrap();
+    assert_eq!(read_file(&mut image, &file).unwrap(), b"longer PSF data");
+    assert_eq!(joliet_data_len(image.get_ref()), 15);
+    let record = &image.get_ref()[file.record_offsets[0] as usize..];
+    assert_eq!(record[14..18], 15u32.to_be_bytes());
+  }
+
+  #[test]
+  fn write_file_rejects_data_larger_than_allocated() {
+    let mut image = Cursor::new(image(b"PSF data", FILE_SECTOR));
+    let file = find_file(&mut image, PS3_PARAM_SFO_PATH).unwrap();
+    let original = image.get_ref().clone();
+
+    assert!(write_file(&mut image, &file, &vec![1; SECTOR_SIZE as usize + 1]).is_err());
+    assert_eq!(image.get_ref(), &original);
+  }
+
+  #[test]
+  fn find_file_rejects_volumes_pointing_at_different_data() {
+    let image = image(b"PSF data", FILE_SECTOR - 1);
+
+    assert!(find_file(&mut Cursor::new(&image), PS3_PARAM_SFO_PATH).is_err());
+  }
+}
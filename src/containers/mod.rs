@@ -0,0 +1,2 @@
+pub mod iso;
+pub mod pbp;
@@ -0,0 +1,326 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use strum::IntoEnumIterator;
+
+use crate::sfo::Sfo;
+
+const PBP_MAGIC: [u8; 4] = [0x00, 0x50, 0x42, 0x50];
+/// Magic and version followed by offsets of all sections.
+const PBP_HEADER_SIZE: u32 = 8 + SECTIONS_COUNT as u32 * 4;
+const SECTIONS_COUNT: usize = 8;
+/// Sections from this one on are not read into memory.
+const FIRST_DATA_SECTION: PbpSection = PbpSection::DataPsp;
+/// Sections following PARAM.SFO keep their alignment as long as its size changes by multiples
+/// of this value.
+const SECTION_ALIGNMENT_BYTES: usize = 16;
+
+/// Sections of the PBP container, in the order in which they are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumIter)]
+pub enum PbpSection {
+  #[strum(serialize = "PARAM.SFO")]
+  ParamSfo,
+  #[strum(serialize = "ICON0.PNG")]
+  Icon0,
+  #[strum(serialize = "ICON1.PMF")]
+  Icon1,
+  #[strum(serialize = "PIC0.PNG")]
+  Pic0,
+  #[strum(serialize = "PIC1.PNG")]
+  Pic1,
+  #[strum(serialize = "SND0.AT3")]
+  Snd0,
+  #[strum(serialize = "DATA.PSP")]
+  DataPsp,
+  #[strum(serialize = "DATA.PSAR")]
+  DataPsar,
+}
+
+/// EBOOT.PBP container of PSP games, PSP minis and PS1 classics. Sections preceding DATA.PSP
+/// are read into memory, while DATA.PSP and DATA.PSAR, which hold the game itself, stay in the
+/// source file and are copied from it on export.
+pub struct Pbp {
+  version: u32,
+  sections: Vec<Vec<u8>>,
+  data_start: u64,
+  data_len: u64,
+  /// Offset of DATA.PSAR relative to the start of DATA.PSP.
+  psar_offset: u32,
+}
+
+/// Version and section offsets of a PBP file, together with the length of the file.
+struct PbpHeader {
+  version: u32,
+  offsets: [u32; SECTIONS_COUNT],
+  file_len: u64,
+}
+
+impl PbpHeader {
+  fn new<T>(reader: &mut T) -> Result<Self, String>
+  where
+    T: Read + Seek,
+  {
+    let mut buffer: [u8; 4] = [0; 4];
+    reader
+      .read_exact(&mut buffer)
+      .map_err(|err| format!("cannot read magic: {err}"))?;
+    if buffer != PBP_MAGIC {
+      return Err(format!(
+        "magic {buffer:#04X?} doesn't match the PBP file magic"
+      ));
+    }
+
+    reader
+      .read_exact(&mut buffer)
+      .map_err(|err| format!("cannot read version: {err}"))?;
+    let version = u32::from_le_bytes(buffer);
+
+    let mut offsets = [0; SECTIONS_COUNT];
+    for (idx, section) in PbpSection::iter().enumerate() {
+      reader
+        .read_exact(&mut buffer)
+        .map_err(|err| format!("cannot read offset of section {section}: {err}"))?;
+      offsets[idx] = u32::from_le_bytes(buffer);
+    }
+
+    let file_len = reader
+      .seek(SeekFrom::End(0))
+      .map_err(|err| format!("cannot read length of the file: {err}"))?;
+    let header = PbpHeader {
+      version,
+      offsets,
+      file_len,
+    };
+
+    for section in PbpSection::iter() {
+      let (start, end) = header.range(section);
+      if start < PBP_HEADER_SIZE as u64 || start > end || end > file_len {
+        return Err(format!(
+          "section {section} spans incorrect range {start:#X}..{end:#X}"
+        ));
+      }
+    }
+
+    Ok(header)
+  }
+
+  /// Start and end offset of the section in the file.
+  fn range(&self, section: PbpSection) -> (u64, u64) {
+    let idx = section as usize;
+    let end = self
+      .offsets
+      .get(idx + 1)
+      .map_or(self.file_len, |offset| *offset as u64);
+    (self.offsets[idx] as u64, end)
+  }
+
+  fn read_section<T>(&self, reader: &mut T, section: PbpSection) -> Result<Vec<u8>, String>
+  where
+    T: Read + Seek,
+  {
+    let (start, end) = self.range(section);
+    let mut data = vec![0; (end - start) as usize];
+    reader
+      .seek(SeekFrom::Start(start))
+      .and_then(|_| reader.read_exact(&mut data))
+      .map_err(|err| format!("cannot read section {section}: {err}"))?;
+    Ok(data)
+  }
+}
+
+impl Pbp {
+  pub fn new<T>(reader: &mut T) -> Result<Self, String>
+  where
+    T: Read + Seek,
+  {
+    let header = PbpHeader::new(reader)?;
+
+    let mut sections = Vec::with_capacity(SECTIONS_COUNT);
+    for section in PbpSection::iter().take_while(|section| *section != FIRST_DATA_SECTION) {
+      sections.push(header.read_section(reader, section)?);
+    }
+
+    let (data_start, _) = header.range(FIRST_DATA_SECTION);
+    let (psar_start, _) = header.range(PbpSection::DataPsar);
+    Ok(Pbp {
+      version: header.version,
+      sections,
+      data_start,
+      data_len: header.file_len - data_start,
+      psar_offset: (psar_start - data_start) as u32,
+    })
+  }
+
+  /// Reads a single section of the PBP file, without reading the other sections.
+  pub fn read_section<T>(reader: &mut T, section: PbpSection) -> Result<Vec<u8>, String>
+  where
+    T: Read + Seek,
+  {
+    PbpHeader::new(reader)?.read_section(reader, section)
+  }
+
+  /// Replaces the PARAM.SFO section with the exported sfo. The section is padded so the
+  /// following sections are shifted by a multiple of their alignment.
+  pub fn set_sfo(&mut self, sfo: &Sfo) -> Result<(), io::Error> {
+    let mut section = Vec::new();
+    sfo.export(&mut section)?;
+
+    let prev_len = self.sections[PbpSection::ParamSfo as usize].len();
+    let misalignment = section.len().abs_diff(prev_len) % SECTION_ALIGNMENT_BYTES;
+    if misalignment != 0 {
+      let padding = match section.len() > prev_len {
+        true => SECTION_ALIGNMENT_BYTES - misalignment,
+        false => misalignment,
+      };
+      section.resize(section.len() + padding, 0);
+    }
+
+    self.sections[PbpSection::ParamSfo as usize] = section;
+    Ok(())
+  }
+
+  /// Writes the PBP file, copying DATA.PSP and DATA.PSAR from the source file it has been read
+  /// from.
+  pub fn export<R, W>(&self, source: &mut R, writer: &mut W) -> Result<(), io::Error>
+  where
+    R: Read + Seek,
+    W: Write,
+  {
+    writer.write_all(&PBP_MAGIC)?;
+    writer.write_all(&self.version.to_le_bytes())?;
+
+    let mut offset = PBP_HEADER_SIZE;
+    for section in self.sections.iter() {
+      writer.write_all(&offset.to_le_bytes())?;
+      offset += section.len() as u32;
+    }
+    writer.write_all(&offset.to_le_bytes())?;
+    writer.write_all(&(offset + self.psar_offset).to_le_bytes())?;
+
+    for section in self.sections.iter() {
+      writer.write_all(section)?;
+    }
+
+    source.seek(SeekFrom::Start(self.data_start))?;
+    let copied = io::copy(&mut source.take(self.data_len), writer)?;
+    if copied != self.data_len {
+      return Err(io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        format!(
+          "source file ended after {copied} of {} bytes of {} and {}",
+          self.data_len,
+          PbpSection::DataPsp,
+          PbpSection::DataPsar
+        ),
+      ));
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use super::*;
+  use crate::sfo::test_fixtures::{self, sfo_with_title};
+
+  fn pbp_bytes(sections: &[Vec<u8>; SECTIONS_COUNT]) -> Vec<u8> {
+    let mut bytes = PBP_MAGIC.to_vec();
+    bytes.extend(0x0001_0000u32.to_le_bytes());
+    let mut offset = PBP_HEADER_SIZE;
+    for section in sections {
+      bytes.extend(offset.to_le_bytes());
+      offset += section.len() as u32;
+    }
+    for section in sections {
+      bytes.extend(section);
+    }
+    bytes
+  }
+
+  fn sample_sections() -> [Vec<u8>; SECTIONS_COUNT] {
+    [
+      test_fixtures::exported(&sfo_with_title("Game")),
+      vec![1; 10],
+      Vec::new(),
+      vec![2; 3],
+      Vec::new(),
+      vec![3; 5],
+      vec![4; 33],
+      vec![5; 7],
+    ]
+  }
+
+  fn offsets(bytes: &[u8]) -> Vec<u32> {
+    bytes[8..PBP_HEADER_SIZE as usize]
+      .chunks(4)
+      .map(|offset| u32::from_le_bytes(offset.try_into().unwrap()))
+      .collect()
+  }
+
+  #[test]
+  fn export_copies_data_sections_from_source() {
+    let bytes = pbp_bytes(&sample_sections());
+    let pbp = Pbp::new(&mut Cursor::new(&bytes)).unwrap();
+
+    let mut exported = Vec::new();
+    pbp.export(&mut Cursor::new(&bytes), &mut exported).unwrap();
+    assert_eq!(exported, bytes);
+  }
+
+  #[test]
+  fn read_section_reads_only_the_section() {
+    let sections = sample_sections();
+    let bytes = pbp_bytes(&sections);
+    for section in PbpSection::iter() {
+      assert_eq!(
+        Pbp::read_section(&mut Cursor::new(&bytes), section).unwrap(),
+        sections[section as usize]
+      );
+    }
+  }
+
+  #[test]
+  fn set_sfo_keeps_following_sections_aligned() {
+    let sections = sample_sections();
+    let bytes = pbp_bytes(&sections);
+    let mut pbp = Pbp::new(&mut Cursor::new(&bytes)).unwrap();
+    let sfo = sfo_with_title("Game of the Year Edition");
+    pbp.set_sfo(&sfo).unwrap();
+
+    let mut exported = Vec::new();
+    pbp.export(&mut Cursor::new(&bytes), &mut exported).unwrap();
+
+    let prev_offsets = offsets(&bytes);
+    let new_offsets = offsets(&exported);
+    assert_eq!(new_offsets[0], prev_offsets[0]);
+    let shift = new_offsets[1] - prev_offsets[1];
+    assert_eq!(shift as usize % SECTION_ALIGNMENT_BYTES, 0);
+    for (prev_offset, new_offset) in prev_offsets.iter().zip(&new_offsets).skip(1) {
+      assert_eq!(new_offset - prev_offset, shift);
+    }
+
+    let param_sfo = Pbp::read_section(&mut Cursor::new(&exported), PbpSection::ParamSfo).unwrap();
+    let sfo_bytes = test_fixtures::exported(&sfo);
+    assert_eq!(&param_sfo[..sfo_bytes.len()], sfo_bytes);
+    assert!(param_sfo[sfo_bytes.len()..].iter().all(|byte| *byte == 0));
+    for section in PbpSection::iter().skip(1) {
+      assert_eq!(
+        Pbp::read_section(&mut Cursor::new(&exported), section).unwrap(),
+        sections[section as usize]
+      );
+    }
+  }
+
+  #[test]
+  fn new_rejects_sections_out_of_order() {
+    let mut bytes = pbp_bytes(&sample_sections());
+    bytes[12..16].copy_from_slice(&0xFFFFu32.to_le_bytes());
+    assert!(Pbp::new(&mut Cursor::new(&bytes)).is_err());
+
+    let mut bytes = pbp_bytes(&sample_sections());
+    bytes[0] = 0xFF;
+    assert!(Pbp::new(&mut Cursor::new(&bytes)).is_err());
+  }
+}
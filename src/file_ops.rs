@@ -0,0 +1,460 @@
+use std::{
+  ffi::OsString,
+  fs::{self, OpenOptions},
+  io::{BufReader, BufWriter, Cursor, Write},
+  path::{Path, PathBuf},
+};
+
+use crate::{
+  assets::Asset,
+  containers::{
+    iso::{self, PS3_ICON0_PATH, PS3_PARAM_SFO_PATH},
+    pbp::{Pbp, PbpSection},
+  },
+  sfo::{
+    Sfo,
+    diff::{diff, mismatches},
+    manifest::build_from_manifest,
+    param_sfx::{export_param_sfx, import_param_sfx},
+  },
+};
+
+pub const PBP_EXTENSIONS: [&str; 2] = ["pbp", "PBP"];
+pub const ISO_EXTENSIONS: [&str; 2] = ["iso", "ISO"];
+pub const SFX_EXTENSIONS: [&str; 2] = ["sfx", "SFX"];
+
+pub fn is_pbp_path<T>(path: T) -> bool
+where
+  T: AsRef<Path>,
+{
+  has_extension(path, &PBP_EXTENSIONS)
+}
+
+pub fn is_iso_path<T>(path: T) -> bool
+where
+  T: AsRef<Path>,
+{
+  has_extension(path, &ISO_EXTENSIONS)
+}
+
+pub fn is_sfx_path<T>(path: T) -> bool
+where
+  T: AsRef<Path>,
+{
+  has_extension(path, &SFX_EXTENSIONS)
+}
+
+fn has_extension<T>(path: T, extensions: &[&str]) -> bool
+where
+  T: AsRef<Path>,
+{
+  path
+    .as_ref()
+    .extension()
+    .is_some_and(|extension| extensions.iter().any(|known| extension == *known))
+}
+
+/// Loads the sfo from a .sfo file, from the PARAM.SFO section of a PBP file, from
+/// PS3_GAME/PARAM.SFO of an ISO image, or from param.sfx XML.
+pub fn load_sfo_file<T>(path: T) -> Result<Sfo, String>
+where
+  T: AsRef<Path>,
+{
+  if is_pbp_path(&path) {
+    let data = read_pbp_section(&path, PbpSection::ParamSfo)?;
+    return Sfo::new(&mut Cursor::new(data))
+      .map_err(|err| format!("could not load {}: {err}", PbpSection::ParamSfo));
+  }
+
+  if is_iso_path(&path) {
+    return load_iso_file(&path);
+  }
+
+  if is_sfx_path(&path) {
+    return load_sfx_file(&path);
+  }
+
+  let file = OpenOptions::new()
+    .read(true)
+    .write(false)
+    .open(&path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut reader = BufReader::new(file);
+  Sfo::new(&mut reader).map_err(|err| format!("could not load file: {err}"))
+}
+
+pub fn load_pbp_file<T>(path: T) -> Result<Pbp, String>
+where
+  T: AsRef<Path>,
+{
+  let file = OpenOptions::new()
+    .read(true)
+    .write(false)
+    .open(&path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut reader = BufReader::new(file);
+  Pbp::new(&mut reader).map_err(|err| format!("could not load file: {err}"))
+}
+
+fn read_pbp_section<T>(path: T, section: PbpSection) -> Result<Vec<u8>, String>
+where
+  T: AsRef<Path>,
+{
+  let file = OpenOptions::new()
+    .read(true)
+    .write(false)
+    .open(&path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut reader = BufReader::new(file);
+  Pbp::read_section(&mut reader, section).map_err(|err| format!("could not load file: {err}"))
+}
+
+/// Builds the sfo from the TOML manifest at the path.
+pub fn load_manifest_file<T>(path: T) -> Result<Sfo, String>
+where
+  T: AsRef<Path>,
+{
+  let path = path.as_ref();
+  let manifest = fs::read_to_string(path)
+    .map_err(|err| format!("could not load manifest {}: {err}", path.display()))?;
+
+  build_from_manifest(&manifest).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Reads ICON0.PNG accompanying the file - the ICON0.PNG section of a PBP file,
+/// PS3_GAME/ICON0.PNG of an ISO image, or ICON0.PNG next to any other file.
+pub fn load_icon_file<T>(path: T) -> Option<Vec<u8>>
+where
+  T: AsRef<Path>,
+{
+  let path = path.as_ref();
+  if is_pbp_path(path) {
+    let icon = read_pbp_section(path, PbpSection::Icon0).ok()?;
+    return (!icon.is_empty()).then_some(icon);
+  }
+
+  if is_iso_path(path) {
+    let mut reader = BufReader::new(fs::File::open(path).ok()?);
+    let iso_file = iso::find_file(&mut reader, PS3_ICON0_PATH).ok()?;
+    return iso::read_file(&mut reader, &iso_file).ok();
+  }
+
+  let icon_path = fs::read_dir(path.parent()?)
+    .ok()?
+    .filter_map(|entry| entry.ok())
+    .find(|entry| {
+      entry
+        .file_name()
+        .to_string_lossy()
+        .eq_ignore_ascii_case(&Asset::Icon0.to_string())
+    })?
+    .path();
+  fs::read(icon_path).ok()
+}
+
+fn load_sfx_file<T>(path: T) -> Result<Sfo, String>
+where
+  T: AsRef<Path>,
+{
+  let file = OpenOptions::new()
+    .read(true)
+    .write(false)
+    .open(&path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut reader = BufReader::new(file);
+  import_param_sfx(&mut reader).map_err(|err| format!("could not load file: {err}"))
+}
+
+fn load_iso_file<T>(path: T) -> Result<Sfo, String>
+where
+  T: AsRef<Path>,
+{
+  let file = OpenOptions::new()
+    .read(true)
+    .write(false)
+    .open(&path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut reader = BufReader::new(file);
+  let iso_file = iso::find_file(&mut reader, PS3_PARAM_SFO_PATH)
+    .map_err(|err| format!("could not load file: {err}"))?;
+  let data =
+    iso::read_file(&mut reader, &iso_file).map_err(|err| format!("could not load file: {err}"))?;
+
+  Sfo::new(&mut Cursor::new(data))
+    .map_err(|err| format!("could not load {PS3_PARAM_SFO_PATH}: {err}"))
+}
+
+/// Overwrites PS3_GAME/PARAM.SFO of the ISO image in place. The image itself is not backed up -
+/// the previous PARAM.SFO is written next to it, see `iso_sfo_backup_path`, and written back from
+/// memory when verification of the saved image fails.
+pub fn save_iso_file<T>(path: T, sfo: &Sfo) -> Result<(), String>
+where
+  T: AsRef<Path>,
+{
+  let path = path.as_ref();
+  let mut data = Vec::new();
+  sfo
+    .export(&mut data)
+    .map_err(|err| format!("could not export sfo: {err}"))?;
+
+  let mut file = OpenOptions::new()
+    .read(true)
+    .write(true)
+    .open(path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+  let iso_file = iso::find_file(&mut file, PS3_PARAM_SFO_PATH)
+    .map_err(|err| format!("could not load file: {err}"))?;
+  if data.len() as u64 > iso_file.allocated_len() {
+    return Err(format!(
+      "could not save file: edited {PS3_PARAM_SFO_PATH} takes {} bytes which does not fit in {} bytes allocated for it in the image",
+      data.len(),
+      iso_file.allocated_len()
+    ));
+  }
+  let backup = iso::read_file(&mut file, &iso_file)
+    .map_err(|err| format!("could not create backup: {err}"))?;
+  let backup_path = iso_sfo_backup_path(path);
+  if !backup_path.exists() {
+    fs::write(&backup_path, &backup)
+      .map_err(|err| format!("could not create backup {}: {err}", backup_path.display()))?;
+  }
+
+  iso::write_file(&mut file, &iso_file, &data)
+    .and_then(|_| verify_iso_file(path, sfo))
+    .map_err(|err| match iso::write_file(&mut file, &iso_file, &backup) {
+      Ok(_) => format!("{err}\nthe previous {PS3_PARAM_SFO_PATH} has been restored"),
+      Err(restore_err) => {
+        format!("{err}\ncould not restore the previous {PS3_PARAM_SFO_PATH}: {restore_err}")
+      }
+    })
+}
+
+/// Writes the sfo back to the file it has been loaded from - a .sfo, .pbp, .iso or .sfx file.
+/// Files are backed up next to them, except for ISO images of which only PARAM.SFO is backed up.
+pub fn save_sfo_in_place<T>(path: T, sfo: &Sfo) -> Result<(), String>
+where
+  T: AsRef<Path>,
+{
+  let path = path.as_ref();
+  if is_pbp_path(path) {
+    return save_pbp_file(path, path, sfo);
+  }
+
+  if is_iso_path(path) {
+    return save_iso_file(path, sfo);
+  }
+
+  if is_sfx_path(path) {
+    return save_sfx_file(path, sfo);
+  }
+
+  save_sfo_file(path, sfo)
+}
+
+/// Writes the sfo to the path, backing up the existing file first. The written file is read back
+/// and compared byte by byte with the exported sfo before it replaces the existing file.
+pub fn save_sfo_file<T>(path: T, sfo: &Sfo) -> Result<(), String>
+where
+  T: AsRef<Path>,
+{
+  save_verified(
+    path.as_ref(),
+    |writer| sfo.export(writer),
+    |path| verify_sfo_file(path, sfo),
+  )
+}
+
+/// Writes the sfo as param.sfx XML, with the same backup as `save_sfo_file`. Layout of the
+/// binary file is not kept in XML, so only entries of the written file are verified.
+pub fn save_sfx_file<T>(path: T, sfo: &Sfo) -> Result<(), String>
+where
+  T: AsRef<Path>,
+{
+  save_verified(
+    path.as_ref(),
+    |writer| export_param_sfx(sfo, writer).map_err(std::io::Error::other),
+    |path| verify_sfx_file(path, sfo),
+  )
+}
+
+/// Writes the PBP file from the source path with its PARAM.SFO section replaced by the sfo,
+/// with the same backup and verification as `save_sfo_file`.
+pub fn save_pbp_file<T, U>(source_path: T, path: U, sfo: &Sfo) -> Result<(), String>
+where
+  T: AsRef<Path>,
+  U: AsRef<Path>,
+{
+  let mut pbp = load_pbp_file(&source_path)?;
+  pbp
+    .set_sfo(sfo)
+    .map_err(|err| format!("could not export sfo: {err}"))?;
+  let mut source = BufReader::new(
+    fs::File::open(&source_path).map_err(|err| format!("could not load file: {err}"))?,
+  );
+
+  save_verified(
+    path.as_ref(),
+    |writer| pbp.export(&mut source, writer),
+    |path| verify_pbp_file(path, sfo),
+  )
+}
+
+/// Writes the file next to the path and verifies it before it replaces the file at the path, so
+/// nothing at the path changes when writing or verification fails. The replaced file is backed
+/// up first, unless its backup already exists.
+fn save_verified<F, V>(path: &Path, export: F, verify: V) -> Result<(), String>
+where
+  F: FnOnce(&mut BufWriter<fs::File>) -> Result<(), std::io::Error>,
+  V: FnOnce(&Path) -> Result<(), String>,
+{
+  let temp_path = sibling_path(path, ".tmp");
+  let result = write_file(&temp_path, export)
+    .and_then(|_| verify(&temp_path))
+    .and_then(|_| match path.exists() {
+      true => create_backup(path),
+      false => Ok(()),
+    })
+    .and_then(|_| {
+      fs::rename(&temp_path, path).map_err(|err| format!("could not save file: {err}"))
+    });
+
+  result.map_err(|err| match temp_path.exists() {
+    true => match fs::remove_file(&temp_path) {
+      Ok(_) => format!("{err}\nthe file has not been changed"),
+      Err(remove_err) => format!(
+        "{err}\nthe file has not been changed, could not remove {}: {remove_err}",
+        temp_path.display()
+      ),
+    },
+    false => err,
+  })
+}
+
+fn write_file<F>(path: &Path, export: F) -> Result<(), String>
+where
+  F: FnOnce(&mut BufWriter<fs::File>) -> Result<(), std::io::Error>,
+{
+  let file = OpenOptions::new()
+    .read(false)
+    .write(true)
+    .create(true)
+    .truncate(true)
+    .open(path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut writer = BufWriter::new(file);
+  export(&mut writer)
+    .and_then(|_| writer.flush())
+    .map_err(|err| format!("could not save file: {err}"))
+}
+
+fn verify_sfo_file(path: &Path, sfo: &Sfo) -> Result<(), String> {
+  let written = fs::read(path).map_err(|err| format!("could not verify saved file: {err}"))?;
+  verify_sfo_bytes(&written, sfo)
+}
+
+fn verify_pbp_file(path: &Path, sfo: &Sfo) -> Result<(), String> {
+  let section = read_pbp_section(path, PbpSection::ParamSfo)
+    .map_err(|err| format!("could not verify saved file: {err}"))?;
+  let (written, padding) = section.split_at(section.len().min(sfo.file_size() as usize));
+  if padding.iter().any(|byte| *byte != 0) {
+    return Err(format!(
+      "saved file does not match the edited file:\n{} section holds data after the sfo",
+      PbpSection::ParamSfo
+    ));
+  }
+
+  verify_sfo_bytes(written, sfo)
+}
+
+fn verify_iso_file(path: &Path, sfo: &Sfo) -> Result<(), String> {
+  let mut reader = BufReader::new(
+    fs::File::open(path).map_err(|err| format!("could not verify saved file: {err}"))?,
+  );
+  let written = iso::find_file(&mut reader, PS3_PARAM_SFO_PATH)
+    .and_then(|iso_file| iso::read_file(&mut reader, &iso_file))
+    .map_err(|err| format!("could not verify saved file: {err}"))?;
+  verify_sfo_bytes(&written, sfo)
+}
+
+/// Compares the written bytes with the exported sfo. Differences are described through
+/// `mismatches` when the written bytes can be read back as a sfo.
+fn verify_sfo_bytes(written: &[u8], sfo: &Sfo) -> Result<(), String> {
+  let mut expected = Vec::new();
+  sfo
+    .export(&mut expected)
+    .map_err(|err| format!("could not export sfo: {err}"))?;
+  if written == expected {
+    return Ok(());
+  }
+
+  let first_difference = written
+    .iter()
+    .zip(&expected)
+    .position(|(written_byte, expected_byte)| written_byte != expected_byte)
+    .unwrap_or(written.len().min(expected.len()));
+  let mut details = vec![format!(
+    "{} bytes written, {} bytes expected, first difference at offset {first_difference:#X}",
+    written.len(),
+    expected.len()
+  )];
+  match Sfo::new(&mut Cursor::new(written)) {
+    Ok(written_sfo) => details.extend(mismatches(sfo, &written_sfo)),
+    Err(err) => details.push(format!("saved file could not be read back: {err}")),
+  }
+
+  Err(format!(
+    "saved file does not match the edited file:\n{}",
+    details.join("\n")
+  ))
+}
+
+fn verify_sfx_file(path: &Path, sfo: &Sfo) -> Result<(), String> {
+  let written_sfo =
+    load_sfx_file(path).map_err(|err| format!("could not verify saved file: {err}"))?;
+
+  let changed_keys: Vec<String> = diff(sfo, &written_sfo)
+    .iter()
+    .map(|entry_diff| format!("entry {} differs", entry_diff.key))
+    .collect();
+  match changed_keys.is_empty() {
+    true => Ok(()),
+    false => Err(format!(
+      "saved file does not match the edited file:\n{}",
+      changed_keys.join("\n")
+    )),
+  }
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+  sibling_path(path, ".bak")
+}
+
+/// Path of the PARAM.SFO copied out of the ISO image before it is first overwritten.
+pub fn iso_sfo_backup_path(path: &Path) -> PathBuf {
+  sibling_path(path, ".PARAM.SFO.bak")
+}
+
+/// Copies the file to its backup path, unless a backup is already there - the backup keeps the
+/// file as it was before it has been first overwritten.
+pub fn create_backup(path: &Path) -> Result<(), String> {
+  let backup_path = backup_path(path);
+  if backup_path.exists() {
+    return Ok(());
+  }
+
+  fs::copy(path, &backup_path)
+    .map(|_| ())
+    .map_err(|err| format!("could not create backup {}: {err}", backup_path.display()))
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+  let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+  file_name.push(suffix);
+  path.with_file_name(file_name)
+}
@@ -0,0 +1,189 @@
+use std::{
+  fmt::Display,
+  fs,
+  path::{Path, PathBuf},
+};
+
+/// Files which accompany PARAM.SFO in game and save data folders.
+pub const ASSET_FILE_NAMES: [&str; 8] = [
+  "ICON0.PNG",
+  "ICON1.PAM",
+  "ICON1.PMF",
+  "PIC0.PNG",
+  "PIC1.PNG",
+  "PIC2.PNG",
+  "SND0.AT3",
+  "PARAM.PFD",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameFolderLayout {
+  /// Root of a PS3 disc with the PS3_GAME directory.
+  Ps3Disc,
+  /// PS3_GAME directory of a disc or a dev_hdd0/game/XXXX directory.
+  Ps3Game,
+  /// PS Vita or PS4 directory with the sce_sys directory.
+  SceSys,
+  /// Save data directory, recognized by its PARAM.PFD.
+  SaveData,
+}
+
+impl Display for GameFolderLayout {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      GameFolderLayout::Ps3Disc => write!(f, "PS3 disc"),
+      GameFolderLayout::Ps3Game => write!(f, "PS3 game"),
+      GameFolderLayout::SceSys => write!(f, "PS Vita/PS4 sce_sys"),
+      GameFolderLayout::SaveData => write!(f, "Save data"),
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct GameFolder {
+  pub root: PathBuf,
+  pub layout: GameFolderLayout,
+  pub sfo_path: PathBuf,
+}
+
+impl GameFolder {
+  /// Recognizes the layout of the folder and finds its PARAM.SFO.
+  pub fn open<T>(root: T) -> Result<Self, String>
+  where
+    T: AsRef<Path>,
+  {
+    let root = root.as_ref();
+    if !root.is_dir() {
+      return Err(format!("{} is not a directory", root.display()));
+    }
+
+    let (layout, sfo_path) = if let Some(sfo_path) = find_entry(root, "PARAM.SFO") {
+      let layout = match find_entry(root, "PARAM.PFD") {
+        Some(_) => GameFolderLayout::SaveData,
+        None => GameFolderLayout::Ps3Game,
+      };
+      (layout, sfo_path)
+    } else if let Some(sfo_path) =
+      find_entry(root, "PS3_GAME").and_then(|dir| find_entry(&dir, "PARAM.SFO"))
+    {
+      (GameFolderLayout::Ps3Disc, sfo_path)
+    } else if let Some(sfo_path) =
+      find_entry(root, "sce_sys").and_then(|dir| find_entry(&dir, "param.sfo"))
+    {
+      (GameFolderLayout::SceSys, sfo_path)
+    } else {
+      return Err(format!(
+        "could not find PARAM.SFO in {} - expected a PS3 game, PS3_GAME, sce_sys or save data directory",
+        root.display()
+      ));
+    };
+
+    Ok(GameFolder {
+      root: root.to_path_buf(),
+      layout,
+      sfo_path,
+    })
+  }
+
+  /// Directory holding PARAM.SFO together with the other assets.
+  pub fn assets_dir(&self) -> &Path {
+    self.sfo_path.parent().unwrap_or(&self.root)
+  }
+
+  /// Path of the asset, matching its name case-insensitively.
+  pub fn asset_path(&self, file_name: &str) -> Option<PathBuf> {
+    find_entry(self.assets_dir(), file_name)
+  }
+
+  /// Assets present in the folder, out of the known ones.
+  pub fn assets(&self) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(self.assets_dir()) else {
+      return Vec::new();
+    };
+
+    let mut assets: Vec<PathBuf> = entries
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| {
+        let file_name = entry.file_name();
+        ASSET_FILE_NAMES
+          .iter()
+          .any(|asset| file_name.to_string_lossy().eq_ignore_ascii_case(asset))
+      })
+      .map(|entry| entry.path())
+      .collect();
+    assets.sort();
+    assets
+  }
+}
+
+fn find_entry(dir: &Path, name: &str) -> Option<PathBuf> {
+  fs::read_dir(dir)
+    .ok()?
+    .filter_map(|entry| entry.ok())
+    .find(|entry| {
+      entry
+        .file_name()
+        .to_string_lossy()
+        .eq_ignore_ascii_case(name)
+    })
+    .map(|entry| entry.path())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Folder in the system temporary directory, unique for the test, holding empty files.
+  fn test_dir(name: &str, files: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+      "sfo-tool-game-folder-{}-{name}",
+      std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    for file in files {
+      let path = dir.join(file);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, []).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn assert_layout(dir: &Path, layout: GameFolderLayout, sfo_path: &str) {
+    let folder = GameFolder::open(dir).unwrap();
+    assert_eq!(folder.layout, layout);
+    assert_eq!(folder.sfo_path, dir.join(sfo_path));
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn open_detects_ps3_disc() {
+    let dir = test_dir("disc", &["PS3_GAME/PARAM.SFO", "PS3_GAME/ICON0.PNG"]);
+    assert_layout(&dir, GameFolderLayout::Ps3Disc, "PS3_GAME/PARAM.SFO");
+  }
+
+  #[test]
+  fn open_detects_ps3_game() {
+    let dir = test_dir("game", &["PARAM.SFO", "USRDIR/EBOOT.BIN"]);
+    assert_layout(&dir, GameFolderLayout::Ps3Game, "PARAM.SFO");
+  }
+
+  #[test]
+  fn open_detects_sce_sys() {
+    let dir = test_dir("sce-sys", &["sce_sys/param.sfo", "eboot.bin"]);
+    assert_layout(&dir, GameFolderLayout::SceSys, "sce_sys/param.sfo");
+  }
+
+  #[test]
+  fn open_detects_save_data() {
+    let dir = test_dir("save", &["param.sfo", "PARAM.PFD", "SYS-DATA"]);
+    assert_layout(&dir, GameFolderLayout::SaveData, "param.sfo");
+  }
+
+  #[test]
+  fn open_fails_without_param_sfo() {
+    let dir = test_dir("empty", &["ICON0.PNG"]);
+    assert!(GameFolder::open(&dir).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
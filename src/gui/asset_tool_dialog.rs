@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+
+use eframe::egui::{self};
+use image::RgbaImage;
+use strum::IntoEnumIterator;
+
+use crate::{
+  assets::{Asset, ResizeMode, load_image, resize_image},
+  game_folder::GameFolderLayout,
+  gui::file_dialogs::load_image_dialog,
+};
+
+/// Converts a chosen image to one of the assets and writes it next to the loaded PARAM.SFO.
+pub struct AssetToolDialog {
+  pub target_dir: PathBuf,
+  layout: GameFolderLayout,
+  pub asset: Asset,
+  resize_mode: ResizeMode,
+  source: Option<(PathBuf, RgbaImage)>,
+  pub converted: Option<RgbaImage>,
+  preview: Option<egui::TextureHandle>,
+  err_msg: Option<String>,
+}
+
+impl AssetToolDialog {
+  pub fn new(target_dir: PathBuf, layout: GameFolderLayout) -> Self {
+    AssetToolDialog {
+      target_dir,
+      layout,
+      asset: Asset::Icon0,
+      resize_mode: ResizeMode::Fit,
+      source: None,
+      converted: None,
+      preview: None,
+      err_msg: None,
+    }
+  }
+
+  fn convert(&mut self, ctx: &egui::Context) {
+    self.preview = None;
+    self.converted = self.source.as_ref().map(|(_, image)| {
+      let size = self
+        .asset
+        .expected_size(self.layout)
+        .unwrap_or(image.dimensions());
+      resize_image(image, size, self.resize_mode)
+    });
+
+    if let Some(converted) = &self.converted {
+      let color_image = egui::ColorImage::from_rgba_unmultiplied(
+        [converted.width() as usize, converted.height() as usize],
+        converted.as_raw(),
+      );
+      self.preview = Some(ctx.load_texture("asset_tool_preview", color_image, Default::default()));
+    }
+  }
+
+  pub fn show(&mut self, ctx: &eframe::egui::Context) -> Option<bool> {
+    egui::Modal::new(egui::Id::new("asset_tool_dialog"))
+      .show(ctx, |ui| {
+        ui.set_width(400.0);
+        ui.heading("Replace asset");
+        ui.label(format!("Target folder: {}", self.target_dir.display()));
+
+        let mut changed = false;
+        ui.horizontal(|ui| {
+          if ui.button("Choose image").clicked() {
+            match load_image_dialog().and_then(|path| Ok((path.clone(), load_image(path)?))) {
+              Ok(source) => {
+                self.source = Some(source);
+                self.err_msg = None;
+                changed = true;
+              }
+              Err(err_msg) => self.err_msg = Some(err_msg),
+            }
+          }
+
+          match &self.source {
+            Some((path, image)) => ui.label(format!(
+              "{} ({}x{})",
+              path.display(),
+              image.width(),
+              image.height()
+            )),
+            None => ui.label("No image chosen"),
+          };
+        });
+
+        ui.horizontal(|ui| {
+          ui.label("Asset");
+          for asset in Asset::iter() {
+            changed |= ui
+              .radio_value(&mut self.asset, asset, asset.to_string())
+              .changed();
+          }
+        });
+
+        ui.horizontal(|ui| {
+          ui.label("Resize");
+          for resize_mode in ResizeMode::iter() {
+            changed |= ui
+              .radio_value(&mut self.resize_mode, resize_mode, resize_mode.to_string())
+              .changed();
+          }
+        });
+
+        match self.asset.expected_size(self.layout) {
+          Some((width, height)) => ui.label(format!("Converted to {width}x{height} PNG")),
+          None => ui.label("Size of the asset is not known, converted to PNG only"),
+        };
+
+        if changed {
+          self.convert(ctx);
+        }
+
+        if let Some(preview) = &self.preview {
+          ui.add(egui::Image::new(preview).max_width(ui.available_width()));
+        }
+
+        if let Some(err_msg) = &self.err_msg {
+          ui.colored_label(ui.visuals().error_fg_color, err_msg);
+        }
+        ui.separator();
+
+        ui.horizontal(|ui| {
+          let ok_btn = ui
+            .add_enabled(self.converted.is_some(), egui::Button::new("Save"))
+            .on_hover_text(
+              "The previous asset is copied to a .bak file unless one exists, so the backup keeps the asset from before the first change",
+            )
+            .on_disabled_hover_text("Choose an image first");
+          if ok_btn.clicked() {
+            return Some(true);
+          }
+
+          let cancel_btn = ui.button("Cancel");
+          if cancel_btn.clicked() {
+            return Some(false);
+          }
+
+          None
+        })
+        .inner
+      })
+      .inner
+  }
+}
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::{
+  assets::{Asset, load_image},
+  game_folder::GameFolder,
+};
+
+/// Previews of the images placed next to the loaded PARAM.SFO.
+#[derive(Default)]
+pub struct AssetsPanel {
+  assets_dir: Option<PathBuf>,
+  previews: HashMap<Asset, AssetPreview>,
+}
+
+struct AssetPreview {
+  texture: egui::TextureHandle,
+  size: (u32, u32),
+}
+
+impl AssetsPanel {
+  /// Loads assets of the folder, unless they have already been loaded for it.
+  pub fn refresh(&mut self, ctx: &egui::Context, game_folder: &GameFolder) -> Vec<String> {
+    let mut errors = Vec::new();
+    if self.assets_dir.as_deref() == Some(game_folder.assets_dir()) {
+      return errors;
+    }
+
+    self.previews.clear();
+    self.assets_dir = Some(game_folder.assets_dir().to_path_buf());
+    for asset in Asset::iter() {
+      let Some(path) = game_folder.asset_path(&asset.to_string()) else {
+        continue;
+      };
+
+      match load_image(&path) {
+        Ok(image) => {
+          let size = image.dimensions();
+          let color_image = egui::ColorImage::from_rgba_unmultiplied(
+            [size.0 as usize, size.1 as usize],
+            image.as_raw(),
+          );
+          let texture = ctx.load_texture(asset.to_string(), color_image, Default::default());
+          self.previews.insert(asset, AssetPreview { texture, size });
+        }
+        Err(err) => errors.push(err),
+      }
+    }
+
+    errors
+  }
+
+  /// Forgets loaded assets, so they are read again on the next refresh.
+  pub fn reset(&mut self) {
+    self.assets_dir = None;
+    self.previews.clear();
+  }
+
+  pub fn texture(&self, asset: Asset) -> Option<&egui::TextureHandle> {
+    self.previews.get(&asset).map(|preview| &preview.texture)
+  }
+
+  pub fn warnings(&self, game_folder: &GameFolder) -> Vec<String> {
+    Asset::iter()
+      .filter_map(|asset| match self.previews.get(&asset) {
+        None if !asset.is_optional() => Some(format!("{asset} is missing")),
+        None => None,
+        Some(preview) => asset
+          .expected_size(game_folder.layout)
+          .filter(|expected_size| *expected_size != preview.size)
+          .map(|(width, height)| {
+            format!(
+              "{asset} is {}x{} while it should be {width}x{height}",
+              preview.size.0, preview.size.1
+            )
+          }),
+      })
+      .collect()
+  }
+
+  pub fn show(&mut self, ctx: &egui::Context, game_folder: &GameFolder) {
+    egui::SidePanel::left("assets_panel")
+      .resizable(true)
+      .show(ctx, |ui| {
+        ui.horizontal(|ui| {
+          ui.heading("Assets");
+          if ui
+            .button("Reload")
+            .on_hover_text("Read the images from the folder again")
+            .clicked()
+          {
+            self.reset();
+          }
+        });
+
+        for warning in self.warnings(game_folder) {
+          ui.colored_label(ui.visuals().warn_fg_color, warning);
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+          for asset in Asset::iter() {
+            ui.strong(asset.to_string());
+            match self.previews.get(&asset) {
+              Some(preview) => {
+                ui.add(egui::Image::new(&preview.texture).max_width(ui.available_width()));
+                ui.label(format!("{}x{}", preview.size.0, preview.size.1));
+              }
+              None => {
+                ui.label("Not present");
+              }
+            }
+            ui.separator();
+          }
+        });
+      });
+  }
+}
@@ -0,0 +1,72 @@
+use eframe::egui::{self};
+
+use crate::sfo::{Sfo, keys::Keys, mapping::DataField};
+
+pub struct ConvertTypeDialog {
+  pub key: Keys,
+  current: String,
+  preview: Result<DataField, String>,
+}
+
+impl ConvertTypeDialog {
+  pub fn new(sfo: &Sfo, key: Keys) -> Self {
+    let current = sfo.get(&key).map_or(String::new(), data_field_description);
+    let preview = sfo.convert_preview(&key);
+
+    ConvertTypeDialog {
+      key,
+      current,
+      preview,
+    }
+  }
+
+  pub fn show(&self, ctx: &eframe::egui::Context) -> Option<bool> {
+    egui::Modal::new(egui::Id::new("convert_type_dialog"))
+      .show(ctx, |ui| {
+        ui.set_width(250.0);
+        ui.heading(format!("Convert type of {}?", self.key));
+
+        egui::Grid::new("convert_type_grid")
+          .num_columns(2)
+          .spacing([20.0, 4.0])
+          .show(ui, |ui| {
+            ui.label("Current");
+            ui.label(&self.current);
+            ui.end_row();
+
+            ui.label("Converted");
+            match &self.preview {
+              Ok(data_field) => ui.label(data_field_description(data_field)),
+              Err(err) => ui.colored_label(ui.visuals().error_fg_color, err),
+            };
+            ui.end_row();
+          });
+        ui.separator();
+
+        ui.horizontal(|ui| {
+          let ok_btn = ui
+            .add_enabled(self.preview.is_ok(), egui::Button::new("Ok"))
+            .on_disabled_hover_text("The value cannot be converted");
+          if ok_btn.clicked() {
+            return Some(true);
+          }
+
+          let cancel_btn = ui.button("Cancel");
+          if cancel_btn.clicked() {
+            return Some(false);
+          }
+
+          None
+        })
+        .inner
+      })
+      .inner
+  }
+}
+
+fn data_field_description(data_field: &DataField) -> String {
+  match data_field {
+    DataField::Utf8String(text) => format!("\"{text}\" (Text)"),
+    DataField::U32(val) => format!("{val} (Number)"),
+  }
+}
@@ -0,0 +1,87 @@
+use eframe::egui;
+use regex::Regex;
+
+use crate::sfo::keys::Keys;
+
+#[derive(Default)]
+pub struct EntriesFilter {
+  query: String,
+  use_regex: bool,
+  regex: Option<Result<Regex, String>>,
+  only_modified: bool,
+  only_unknown_keys: bool,
+  only_invalid: bool,
+}
+
+/// Properties of a grid row the filter is matched against.
+pub struct FilteredEntry<'a> {
+  pub key: &'a Keys,
+  pub values: &'a [String],
+  pub modified: bool,
+  pub invalid: bool,
+}
+
+impl EntriesFilter {
+  pub fn show(&mut self, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+      ui.label("Filter");
+      let query_input =
+        ui.add(egui::TextEdit::singleline(&mut self.query).hint_text("Key name or value"));
+      let regex_checkbox = ui.checkbox(&mut self.use_regex, "Regex");
+      if query_input.changed() || regex_checkbox.changed() {
+        self.regex = None;
+      }
+
+      ui.separator();
+      ui.checkbox(&mut self.only_modified, "Modified");
+      ui.checkbox(&mut self.only_unknown_keys, "Unknown keys");
+      ui.checkbox(&mut self.only_invalid, "Failing validation");
+    });
+
+    if self.use_regex
+      && let Err(err) = self.compiled_regex()
+    {
+      ui.colored_label(ui.visuals().error_fg_color, err);
+    }
+  }
+
+  pub fn matches(&mut self, entry: &FilteredEntry) -> bool {
+    if (self.only_modified && !entry.modified)
+      || (self.only_unknown_keys && !matches!(entry.key, Keys::Unknown(_)))
+      || (self.only_invalid && !entry.invalid)
+    {
+      return false;
+    }
+
+    if self.query.is_empty() {
+      return true;
+    }
+
+    let key = entry.key.to_string();
+    match self.use_regex {
+      true => match self.compiled_regex() {
+        Ok(regex) => regex.is_match(&key) || entry.values.iter().any(|value| regex.is_match(value)),
+        Err(_) => true,
+      },
+      false => {
+        let query = self.query.to_lowercase();
+        key.to_lowercase().contains(&query)
+          || entry
+            .values
+            .iter()
+            .any(|value| value.to_lowercase().contains(&query))
+      }
+    }
+  }
+
+  fn compiled_regex(&mut self) -> Result<&Regex, String> {
+    let query = &self.query;
+    self
+      .regex
+      .get_or_insert_with(|| {
+        Regex::new(query).map_err(|err| format!("invalid regular expression: {err}"))
+      })
+      .as_ref()
+      .map_err(|err| err.clone())
+  }
+}
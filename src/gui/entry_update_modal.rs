@@ -2,14 +2,25 @@ use std::str::FromStr;
 
 use eframe::egui::{self, Id, TextBuffer};
 use egui_typed_input::ValText;
+use strum::IntoEnumIterator;
 
-use crate::sfo::{SfoEntry, keys::Keys, mapping::DataField};
+use crate::{
+  gui::u32_display_mode::{U32DisplayMode, u32_input},
+  sfo::{Sfo, SfoEntry, format::Format, keys::Keys, mapping::DataField},
+};
 
 pub struct EntryUpdateModal {
   key: String,
   data_field_string_value: String,
   data_field_num_value: Option<u32>,
+  data_field_num_input: ValText<u32, String>,
   data_field_variant: DataFieldVariant,
+  reserved_len: Option<u32>,
+  null_terminated: bool,
+  grow_reserved_len: bool,
+  existing_keys: Vec<Keys>,
+  original_key: Option<Keys>,
+  unknown_key_confirmed: bool,
   pub variant: ModalVariant,
 }
 
@@ -38,29 +49,46 @@ pub enum DataFieldVariant {
 }
 
 impl EntryUpdateModal {
-  pub fn new_add_entry_modal() -> Self {
+  pub fn new_add_entry_modal(sfo: &Sfo) -> Self {
     EntryUpdateModal {
       variant: ModalVariant::Add,
       data_field_variant: Default::default(),
       data_field_num_value: None,
+      data_field_num_input: u32_input(),
       data_field_string_value: Default::default(),
       key: Default::default(),
+      reserved_len: None,
+      null_terminated: true,
+      grow_reserved_len: false,
+      existing_keys: sfo.iter().map(|(key, _)| key.clone()).collect(),
+      original_key: None,
+      unknown_key_confirmed: false,
     }
   }
 
-  pub fn new_update_entry_modal(key: &Keys, entry: &SfoEntry) -> Self {
+  pub fn new_update_entry_modal(
+    sfo: &Sfo,
+    key: &Keys,
+    entry: &SfoEntry,
+    num_display_mode: U32DisplayMode,
+  ) -> Self {
     let data_field_variant = match entry.index_table_entry.data_format {
-      crate::sfo::format::Format::Utf8 | crate::sfo::format::Format::Utf8Special => {
-        DataFieldVariant::Text
-      }
-      crate::sfo::format::Format::U32 => DataFieldVariant::Number,
+      Format::Utf8 | Format::Utf8Special => DataFieldVariant::Text,
+      Format::U32 => DataFieldVariant::Number,
     };
     let mut modal = EntryUpdateModal {
       variant: ModalVariant::Edit,
       key: key.to_string(),
       data_field_variant,
       data_field_num_value: None,
+      data_field_num_input: u32_input(),
       data_field_string_value: Default::default(),
+      reserved_len: Some(entry.index_table_entry.data_max_len),
+      null_terminated: !matches!(entry.index_table_entry.data_format, Format::Utf8Special),
+      grow_reserved_len: false,
+      existing_keys: sfo.iter().map(|(key, _)| key.clone()).collect(),
+      original_key: Some(key.clone()),
+      unknown_key_confirmed: false,
     };
     match entry.data {
       DataField::Utf8String(text) => {
@@ -68,11 +96,163 @@ impl EntryUpdateModal {
       }
       DataField::U32(val) => {
         modal.data_field_num_value = Some(*val);
+        modal
+          .data_field_num_input
+          .replace_with(&num_display_mode.format(*val));
       }
     };
     modal
   }
 
+  fn select_known_key(&mut self, key: &Keys) {
+    self.key = key.to_string();
+    self.unknown_key_confirmed = false;
+    if let ModalVariant::Edit = self.variant {
+      return;
+    }
+
+    self.data_field_variant = match key.format() {
+      Some(Format::U32) => DataFieldVariant::Number,
+      _ => DataFieldVariant::Text,
+    };
+
+    if let Some((suggested_value, _)) = key.suggested_values().into_iter().next() {
+      self.set_suggested_value(suggested_value);
+    }
+  }
+
+  fn set_suggested_value(&mut self, value: DataField) {
+    match value {
+      DataField::Utf8String(text) => {
+        self.data_field_variant = DataFieldVariant::Text;
+        self.data_field_string_value = text;
+      }
+      DataField::U32(val) => {
+        self.data_field_variant = DataFieldVariant::Number;
+        self.data_field_num_value = Some(val);
+        self.data_field_num_input.replace_with(&val.to_string());
+      }
+    }
+  }
+
+  fn key_err(&self) -> Option<String> {
+    let key = Keys::from_str(&self.key).ok()?;
+    if self.original_key.as_ref() == Some(&key) {
+      return None;
+    }
+
+    if self.existing_keys.contains(&key) {
+      return Some(format!("Entry with key {key} already exists"));
+    }
+
+    if matches!(key, Keys::Unknown(_)) && !self.unknown_key_confirmed {
+      return Some(format!(
+        "Key {key} is not recognised, please confirm saving it"
+      ));
+    }
+
+    None
+  }
+
+  fn known_keys_combo_box(&mut self, ui: &mut egui::Ui) {
+    let filter = self.key.to_uppercase();
+    let mut selected_key: Option<Keys> = None;
+
+    egui::ComboBox::from_id_salt("known_keys_combo_box")
+      .selected_text("Known keys")
+      .height(300.0)
+      .show_ui(ui, |ui| {
+        for key in
+          Keys::iter().filter(|key| !matches!(key, Keys::Unknown(_)) && !key.is_placeholder())
+        {
+          let key_name = key.to_string();
+          if !key_name.contains(&filter) {
+            continue;
+          }
+
+          let exists = self.existing_keys.contains(&key);
+          let label = match exists {
+            true => format!("{key_name} (in file)"),
+            false => key_name,
+          };
+          let key_btn = ui
+            .add_enabled(!exists, egui::Button::selectable(false, label))
+            .on_disabled_hover_text("Entry with this key already exists in the file");
+          if key_btn.clicked() {
+            selected_key = Some(key);
+          }
+        }
+      });
+
+    if let Some(key) = selected_key {
+      self.select_known_key(&key);
+    }
+  }
+
+  fn suggested_values_combo_box(&mut self, ui: &mut egui::Ui, key: &Keys) {
+    let mut selected_value: Option<DataField> = None;
+
+    egui::ComboBox::from_id_salt("suggested_values_combo_box")
+      .selected_text("Suggested values")
+      .show_ui(ui, |ui| {
+        for (value, description) in key.suggested_values() {
+          if ui
+            .selectable_label(false, format!("{value} - {description}"))
+            .clicked()
+          {
+            selected_value = Some(value);
+          }
+        }
+      });
+
+    if let Some(value) = selected_value {
+      self.set_suggested_value(value);
+    }
+  }
+
+  fn text_len(&self) -> u32 {
+    let null_terminator_len = match self.null_terminated {
+      true => 1,
+      false => 0,
+    };
+    self.data_field_string_value.len() as u32 + null_terminator_len
+  }
+
+  fn text_len_err(&self) -> Option<String> {
+    let text_len = self.text_len();
+    let key = Keys::from_str(&self.key).ok()?;
+    if let Some(max_len) = key.max_len()
+      && text_len > max_len
+    {
+      return Some(format!(
+        "Value takes {text_len} bytes which exceeds maximum of {max_len} bytes for key {key}"
+      ));
+    }
+
+    if let Some(reserved_len) = self.reserved_len
+      && text_len > reserved_len
+      && !self.grow_reserved_len
+    {
+      return Some(format!(
+        "Value takes {text_len} bytes which exceeds reserved size of {reserved_len} bytes"
+      ));
+    }
+
+    None
+  }
+
+  fn text_len_label(&self) -> String {
+    let text_len = self.text_len();
+    let key_max_len = Keys::from_str(&self.key).ok().and_then(|key| key.max_len());
+    match (self.reserved_len, key_max_len) {
+      (Some(reserved_len), Some(max_len)) => {
+        format!("{text_len} / {reserved_len} bytes (key maximum {max_len} bytes)")
+      }
+      (Some(len), None) | (None, Some(len)) => format!("{text_len} / {len} bytes"),
+      (None, None) => format!("{text_len} bytes"),
+    }
+  }
+
   pub fn show(&mut self, ctx: &eframe::egui::Context) -> Result<EntryUpdateModalAction, String> {
     let modal = egui::Modal::new(Id::new("draft_entry_modal")).show(ctx, |ui| {
       ui.set_width(250.0);
@@ -81,13 +261,17 @@ impl EntryUpdateModal {
         ModalVariant::Edit => ui.heading("Edit entry"),
       };
 
-      ui.horizontal(|ui| {
-        ui.radio_value(&mut self.data_field_variant, DataFieldVariant::Text, "Text");
-        ui.radio_value(
-          &mut self.data_field_variant,
-          DataFieldVariant::Number,
-          "Number",
-        );
+      ui.add_enabled_ui(matches!(self.variant, ModalVariant::Add), |ui| {
+        ui.horizontal(|ui| {
+          ui.radio_value(&mut self.data_field_variant, DataFieldVariant::Text, "Text");
+          ui.radio_value(
+            &mut self.data_field_variant,
+            DataFieldVariant::Number,
+            "Number",
+          );
+        })
+        .response
+        .on_disabled_hover_text("Use \"Type\" action of the entry row to convert its data type");
       });
       egui::Grid::new("draft_entry_grid")
         .num_columns(2)
@@ -99,28 +283,90 @@ impl EntryUpdateModal {
           ui.label("Key");
           match self.variant {
             ModalVariant::Add => {
-              ui.text_edit_singleline(&mut self.key);
-            },
+              ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.key);
+                self.known_keys_combo_box(ui);
+              });
+            }
             ModalVariant::Edit => {
-              ui.label(&self.key).on_hover_text("Key edit is disabled when editing row. Please either add a new entry with a new key or remove an existing entry row");
-            },
+              ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.key).on_hover_text(
+                  "Changing the key renames the entry, keeping its data format and reserved size",
+                );
+                self.known_keys_combo_box(ui);
+              });
+            }
           };
           ui.end_row();
 
+          if let Ok(key) = Keys::from_str(&self.key) {
+            if let ModalVariant::Add = self.variant
+              && !key.suggested_values().is_empty()
+            {
+              ui.label("");
+              self.suggested_values_combo_box(ui, &key);
+              ui.end_row();
+            }
+
+            if let Keys::Unknown(_) = key
+              && !self.key.is_empty()
+              && self.original_key.as_ref() != Some(&key)
+            {
+              ui.label("");
+              ui.vertical(|ui| {
+                ui.colored_label(
+                  ui.visuals().warn_fg_color,
+                  format!("Key {key} is not recognised"),
+                );
+                ui.checkbox(&mut self.unknown_key_confirmed, "Save unrecognised key");
+              });
+              ui.end_row();
+            }
+          }
+
           ui.label("Data");
           match self.data_field_variant {
             DataFieldVariant::Text => {
-              ui.text_edit_singleline(&mut self.data_field_string_value);
+              let multiline = Keys::from_str(&self.key).is_ok_and(|key| key.is_multiline());
+              match multiline {
+                true => ui.text_edit_multiline(&mut self.data_field_string_value),
+                false => ui.text_edit_singleline(&mut self.data_field_string_value),
+              };
+              ui.end_row();
+
+              ui.label("Size");
+              ui.label(self.text_len_label())
+                .on_hover_text(match self.null_terminated {
+                  true => "Size of the value encoded as UTF-8, including null terminator",
+                  false => "Size of the value encoded as UTF-8, stored without null terminator",
+                });
+              let text_len = self.text_len();
+              if let Some(reserved_len) = self.reserved_len
+                && text_len > reserved_len
+              {
+                ui.end_row();
+
+                ui.label("");
+                ui.checkbox(
+                  &mut self.grow_reserved_len,
+                  format!("Grow reserved size from {reserved_len} to {text_len} bytes"),
+                );
+              }
             }
             DataFieldVariant::Number => {
-              let mut num_input = ValText::<u32, <u32 as FromStr>::Err>::number_uint();
-              if let Some(val) = self.data_field_num_value {
-                num_input.set_val(val);
+              let num_input = ui
+                .text_edit_singleline(&mut self.data_field_num_input)
+                .on_hover_text("Decimal, 0x-prefixed hexadecimal or 0b-prefixed binary number. Flags can be joined with \"|\"");
+              if num_input.changed() {
+                self.data_field_num_value =
+                  self.data_field_num_input.get_val().and_then(|val| val.ok()).copied();
+              }
+              if let Some(Err(err)) = self.data_field_num_input.get_val() {
+                ui.end_row();
+
+                ui.label("");
+                ui.colored_label(ui.visuals().error_fg_color, err);
               }
-              ui.text_edit_singleline(&mut num_input);
-              if let Some(Ok(new_val)) = num_input.get_val() {
-                self.data_field_num_value = Some(*new_val);
-              };
             }
           }
           ui.end_row();
@@ -133,12 +379,19 @@ impl EntryUpdateModal {
           DataFieldVariant::Text => !self.data_field_string_value.is_empty(),
           DataFieldVariant::Number => self.data_field_num_value.is_some(),
         };
+        let value_err = match self.data_field_variant {
+          DataFieldVariant::Text => self.text_len_err(),
+          DataFieldVariant::Number => None,
+        };
+        let value_err = self.key_err().or(value_err);
         let ok_btn = ui
           .add_enabled(
-            !self.key.is_empty() && value_filled,
+            !self.key.is_empty() && value_filled && value_err.is_none(),
             egui::Button::new("Ok"),
           )
-          .on_disabled_hover_text("Cannot add an entry with empty key or field");
+          .on_disabled_hover_text(
+            value_err.unwrap_or(String::from("Cannot add an entry with empty key or field")),
+          );
         if ok_btn.clicked() {
           return ModalAction::Ok;
         }
@@ -162,9 +415,15 @@ impl EntryUpdateModal {
           DataFieldVariant::Number => DataField::U32(self.data_field_num_value.unwrap_or_default()),
         };
 
+        let renamed_from = self
+          .original_key
+          .take()
+          .filter(|original_key| *original_key != draft_entry_key);
+
         return Ok(EntryUpdateModalAction::Save(DraftEntry {
           key: draft_entry_key,
           field: draft_entry_field,
+          renamed_from,
         }));
       }
       ModalAction::Cancel => {
@@ -184,6 +443,7 @@ impl EntryUpdateModal {
 pub struct DraftEntry {
   pub key: Keys,
   pub field: DataField,
+  pub renamed_from: Option<Keys>,
 }
 
 impl Default for DraftEntry {
@@ -191,6 +451,7 @@ impl Default for DraftEntry {
     Self {
       key: Keys::Unknown(String::new()),
       field: DataField::Utf8String(String::new()),
+      renamed_from: None,
     }
   }
 }
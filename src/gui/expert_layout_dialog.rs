@@ -0,0 +1,190 @@
+use eframe::egui::{self};
+
+use crate::sfo::{
+  Sfo,
+  keys::Keys,
+  validation::{ValidationIssue, validate},
+};
+
+/// Edits raw layout fields on a copy of the sfo. The copy is rebuilt on every change, so the
+/// recomputed offsets and validation results can be previewed before it is applied.
+pub struct ExpertLayoutDialog {
+  original: Sfo,
+  pub sfo: Sfo,
+  version: u32,
+  padding: u32,
+  data_max_lens: Vec<(Keys, u32)>,
+  err_msg: Option<String>,
+  issues: Vec<ValidationIssue>,
+  existing_issues: Vec<String>,
+  remaining_issues: Vec<String>,
+}
+
+impl ExpertLayoutDialog {
+  pub fn new(sfo: &Sfo) -> Self {
+    let data_max_lens = sfo
+      .iter()
+      .map(|(key, entry)| (key.clone(), entry.index_table_entry.data_max_len))
+      .collect();
+
+    let existing_issues = validate(sfo).iter().map(issue_message).collect();
+    let mut dialog = ExpertLayoutDialog {
+      original: sfo.clone(),
+      sfo: sfo.clone(),
+      version: sfo.header.version,
+      padding: sfo.padding,
+      data_max_lens,
+      err_msg: None,
+      issues: Vec::new(),
+      existing_issues,
+      remaining_issues: Vec::new(),
+    };
+    dialog.rebuild();
+    dialog
+  }
+
+  fn rebuild(&mut self) {
+    let mut sfo = self.original.clone();
+    sfo.header.version = self.version;
+    let resized = self
+      .data_max_lens
+      .iter()
+      .try_for_each(|(key, data_max_len)| sfo.resize(key, *data_max_len));
+    if self.padding != sfo.padding {
+      sfo.set_padding(self.padding);
+    }
+
+    self.err_msg = resized.err();
+    self.remaining_issues.clear();
+    self.issues.clear();
+    for issue in validate(&sfo) {
+      let message = issue_message(&issue);
+      match self.existing_issues.contains(&message) {
+        true => self.remaining_issues.push(message),
+        false => self.issues.push(issue),
+      }
+    }
+    self.sfo = sfo;
+  }
+
+  pub fn show(&mut self, ctx: &eframe::egui::Context) -> Option<bool> {
+    egui::Modal::new(egui::Id::new("expert_layout_dialog"))
+      .show(ctx, |ui| {
+        ui.set_width(500.0);
+        ui.heading("Expert layout");
+        ui.colored_label(
+          ui.visuals().warn_fg_color,
+          "Raw fields are written as they are - other tools may not read unusual layouts",
+        );
+        ui.separator();
+
+        let mut changed = false;
+        egui::Grid::new("expert_layout_header_grid")
+          .num_columns(2)
+          .spacing([20.0, 4.0])
+          .show(ui, |ui| {
+            ui.label("Version");
+            ui.horizontal(|ui| {
+              changed |= ui
+                .add(egui::DragValue::new(&mut self.version).hexadecimal(8, false, true))
+                .changed();
+              ui.label(self.sfo.header.version_name());
+            });
+            ui.end_row();
+
+            ui.label("Key table padding");
+            changed |= ui
+              .add(egui::DragValue::new(&mut self.padding).range(0..=u16::MAX))
+              .on_hover_text(
+                "Changed padding is kept when entries are added, renamed or removed later",
+              )
+              .changed();
+            ui.end_row();
+
+            ui.label("Key table start");
+            ui.monospace(format!("{:#010X}", self.sfo.header.key_table_start));
+            ui.end_row();
+
+            ui.label("Data table start");
+            ui.monospace(format!("{:#010X}", self.sfo.header.data_table_start));
+            ui.end_row();
+
+            ui.label("File size");
+            ui.monospace(format!("{} bytes", self.sfo.file_size()));
+            ui.end_row();
+          });
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+          .max_height(300.0)
+          .show(ui, |ui| {
+            egui::Grid::new("expert_layout_entries_grid")
+              .num_columns(4)
+              .spacing([20.0, 4.0])
+              .striped(true)
+              .show(ui, |ui| {
+                for header in ["KEY", "LEN", "MAX LEN", "DATA OFFSET"] {
+                  ui.strong(header);
+                }
+                ui.end_row();
+
+                for (key, data_max_len) in self.data_max_lens.iter_mut() {
+                  let entry = self.sfo.get_entry(key);
+                  ui.label(key.to_string());
+                  ui.monospace(entry.as_ref().map_or(String::new(), |entry| {
+                    entry.index_table_entry.data_len.to_string()
+                  }));
+                  changed |= ui.add(egui::DragValue::new(data_max_len)).changed();
+                  ui.monospace(entry.as_ref().map_or(String::new(), |entry| {
+                    format!("{:#010X}", entry.index_table_entry.data_offset)
+                  }));
+                  ui.end_row();
+                }
+              });
+          });
+        ui.separator();
+
+        if let Some(err_msg) = &self.err_msg {
+          ui.colored_label(ui.visuals().error_fg_color, err_msg);
+        }
+        for issue in &self.issues {
+          ui.colored_label(ui.visuals().warn_fg_color, issue_message(issue));
+        }
+        for issue in &self.remaining_issues {
+          ui.weak(format!("{issue} (already in the loaded file)"));
+        }
+
+        if changed {
+          self.rebuild();
+        }
+
+        ui.horizontal(|ui| {
+          let ok_btn = ui
+            .add_enabled(
+              self.err_msg.is_none() && !self.issues.iter().any(|issue| issue.is_error()),
+              egui::Button::new("Apply"),
+            )
+            .on_disabled_hover_text("The changed layout introduces validation issues");
+          if ok_btn.clicked() {
+            return Some(true);
+          }
+
+          let cancel_btn = ui.button("Cancel");
+          if cancel_btn.clicked() {
+            return Some(false);
+          }
+
+          None
+        })
+        .inner
+      })
+      .inner
+  }
+}
+
+fn issue_message(issue: &ValidationIssue) -> String {
+  match &issue.key {
+    Some(key) => format!("{key}: {issue}"),
+    None => issue.to_string(),
+  }
+}
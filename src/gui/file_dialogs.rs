@@ -1,31 +1,167 @@
-use std::{fs::OpenOptions, io::BufWriter, path::PathBuf};
+use std::{
+  fs::{self, OpenOptions},
+  io::{BufReader, BufWriter},
+  path::{Path, PathBuf},
+};
 
 use rfd::FileDialog;
 
-use crate::{gui::file_ops::load_sfo_file, sfo::Sfo};
+use crate::{
+  file_ops::{
+    ISO_EXTENSIONS, PBP_EXTENSIONS, SFX_EXTENSIONS, is_pbp_path, load_sfo_file, save_pbp_file,
+    save_sfo_file, save_sfx_file,
+  },
+  game_folder::GameFolder,
+  library::{LibraryEntry, LibraryImportPlan, export_library_csv, plan_library_csv_import},
+  report::ReportFormat,
+  sfo::{
+    Sfo,
+    keys::Keys,
+    languages::{Language, export_titles_csv, import_titles_csv},
+  },
+};
 
 const FORMAT_NAME: &str = "System File Object";
 const EXTENSIONS: [&str; 2] = ["sfo", "SFO"];
+const PBP_FORMAT_NAME: &str = "PSP EBOOT";
+const ISO_FORMAT_NAME: &str = "PS3 disc image";
+const SFX_FORMAT_NAME: &str = "param.sfx XML";
+const IMAGE_FORMAT_NAME: &str = "PNG or JPEG image";
+const IMAGE_EXTENSIONS: [&str; 6] = ["png", "PNG", "jpg", "JPG", "jpeg", "JPEG"];
+const REPORT_FORMAT_NAME: &str = "Report";
+const CSV_FORMAT_NAME: &str = "Comma-separated values";
+const CSV_EXTENSIONS: [&str; 2] = ["csv", "CSV"];
 
-pub fn save_sfo_dialog(sfo: &Sfo) -> Result<(), String> {
-  let file_dialog_result = FileDialog::new()
-    .add_filter(FORMAT_NAME, &EXTENSIONS)
+/// Picks a path to save a file to, adding the first of the extensions when the picked name has
+/// none.
+fn save_path_dialog(filter_name: &str, extensions: &[&str]) -> Result<PathBuf, String> {
+  let mut path = FileDialog::new()
+    .add_filter(filter_name, extensions)
     .set_directory("/")
-    .save_file();
+    .save_file()
+    .ok_or_else(|| String::from("No file has been selected"))?;
+  if path.extension().is_none()
+    && let Some(extension) = extensions.first()
+  {
+    path.set_extension(extension);
+  }
+
+  Ok(path)
+}
+
+/// Saves the sfo to a picked file, returning its path.
+pub fn save_sfo_dialog(sfo: &Sfo) -> Result<PathBuf, String> {
+  let path = save_path_dialog(FORMAT_NAME, &EXTENSIONS)?;
+  save_sfo_file(&path, sfo)?;
+  Ok(path)
+}
 
-  let path = match file_dialog_result {
-    Some(mut path) => {
-      if path.extension().is_none() {
-        path.set_extension(EXTENSIONS[0]);
-      }
+pub fn save_sfx_dialog(sfo: &Sfo) -> Result<PathBuf, String> {
+  let path = save_path_dialog(SFX_FORMAT_NAME, &SFX_EXTENSIONS)?;
+  save_sfx_file(&path, sfo)?;
+  Ok(path)
+}
+
+/// Saves the sfo into a copy of the PBP file from the source path.
+pub fn save_pbp_dialog(sfo: &Sfo, source_path: &Path) -> Result<PathBuf, String> {
+  let mut path = save_path_dialog(PBP_FORMAT_NAME, &PBP_EXTENSIONS)?;
+  // A PBP extension is forced so that the copy is opened as a PBP file again.
+  if !is_pbp_path(&path) {
+    path.set_extension(PBP_EXTENSIONS[0]);
+  }
+
+  save_pbp_file(source_path, &path, sfo)?;
+  Ok(path)
+}
 
-      path
+pub fn load_sfo_dialog() -> Result<(Sfo, PathBuf), String> {
+  let files = FileDialog::new()
+    .add_filter(FORMAT_NAME, &EXTENSIONS)
+    .add_filter(PBP_FORMAT_NAME, &PBP_EXTENSIONS)
+    .add_filter(ISO_FORMAT_NAME, &ISO_EXTENSIONS)
+    .add_filter(SFX_FORMAT_NAME, &SFX_EXTENSIONS)
+    .set_directory("/")
+    .pick_file();
+
+  let path = match files {
+    Some(path) => path,
+    None => {
+      return Err(String::from("No file has been selected"));
     }
+  };
+
+  let sfo = load_sfo_file(&path)?;
+  Ok((sfo, path))
+}
+
+pub fn open_game_folder_dialog() -> Result<GameFolder, String> {
+  let folder = FileDialog::new().set_directory("/").pick_folder();
+
+  match folder {
+    Some(path) => GameFolder::open(path),
+    None => Err(String::from("No folder has been selected")),
+  }
+}
+
+pub fn pick_library_folder_dialog() -> Result<PathBuf, String> {
+  FileDialog::new()
+    .set_directory("/")
+    .pick_folder()
+    .ok_or_else(|| String::from("No folder has been selected"))
+}
+
+pub fn load_image_dialog() -> Result<PathBuf, String> {
+  FileDialog::new()
+    .add_filter(IMAGE_FORMAT_NAME, &IMAGE_EXTENSIONS)
+    .set_directory("/")
+    .pick_file()
+    .ok_or_else(|| String::from("No file has been selected"))
+}
+
+pub fn save_report_dialog(report: &str, format: ReportFormat) -> Result<(), String> {
+  let path = save_path_dialog(REPORT_FORMAT_NAME, &[format.extension()])?;
+  fs::write(path, report).map_err(|err| format!("could not save file: {err}"))
+}
+
+pub fn save_titles_csv_dialog(sfo: &Sfo) -> Result<(), String> {
+  let path = save_path_dialog(CSV_FORMAT_NAME, &CSV_EXTENSIONS)?;
+  let file = OpenOptions::new()
+    .read(false)
+    .write(true)
+    .create(true)
+    .truncate(true)
+    .open(path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut writer = BufWriter::new(file);
+  export_titles_csv(sfo, &mut writer).map_err(|err| format!("could not save file: {err}"))
+}
+
+pub fn load_titles_csv_dialog() -> Result<Vec<(Language, Option<String>)>, String> {
+  let files = FileDialog::new()
+    .add_filter(CSV_FORMAT_NAME, &CSV_EXTENSIONS)
+    .set_directory("/")
+    .pick_file();
+
+  let path = match files {
+    Some(path) => path,
     None => {
       return Err(String::from("No file has been selected"));
     }
   };
 
+  let file = OpenOptions::new()
+    .read(true)
+    .write(false)
+    .open(&path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut reader = BufReader::new(file);
+  import_titles_csv(&mut reader).map_err(|err| format!("could not load file: {err}"))
+}
+
+pub fn save_library_csv_dialog(entries: &[LibraryEntry], keys: &[Keys]) -> Result<(), String> {
+  let path = save_path_dialog(CSV_FORMAT_NAME, &CSV_EXTENSIONS)?;
   let file = OpenOptions::new()
     .read(false)
     .write(true)
@@ -35,14 +171,13 @@ pub fn save_sfo_dialog(sfo: &Sfo) -> Result<(), String> {
     .map_err(|err| format!("could not load file: {err}"))?;
 
   let mut writer = BufWriter::new(file);
-  sfo
-    .export(&mut writer)
-    .map_err(|err| format!("could not save file: {}", err))
+  export_library_csv(entries, keys, &mut writer)
+    .map_err(|err| format!("could not save file: {err}"))
 }
 
-pub fn load_sfo_dialog() -> Result<(Sfo, PathBuf), String> {
+pub fn load_library_csv_dialog() -> Result<LibraryImportPlan, String> {
   let files = FileDialog::new()
-    .add_filter(FORMAT_NAME, &EXTENSIONS)
+    .add_filter(CSV_FORMAT_NAME, &CSV_EXTENSIONS)
     .set_directory("/")
     .pick_file();
 
@@ -53,6 +188,12 @@ pub fn load_sfo_dialog() -> Result<(Sfo, PathBuf), String> {
     }
   };
 
-  let sfo = load_sfo_file(&path)?;
-  Ok((sfo, path))
+  let file = OpenOptions::new()
+    .read(true)
+    .write(false)
+    .open(&path)
+    .map_err(|err| format!("could not load file: {err}"))?;
+
+  let mut reader = BufReader::new(file);
+  plan_library_csv_import(&mut reader).map_err(|err| format!("could not load file: {err}"))
 }
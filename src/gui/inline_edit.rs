@@ -0,0 +1,132 @@
+use eframe::egui::{self, TextBuffer};
+use egui_typed_input::ValText;
+
+use crate::{
+  gui::u32_display_mode::{U32DisplayMode, u32_input},
+  sfo::{SfoEntry, format::Format, keys::Keys, mapping::DataField},
+};
+
+pub struct InlineEdit {
+  pub key: Keys,
+  value: InlineEditValue,
+  reserved_len: u32,
+  null_terminated: bool,
+  err_msg: Option<String>,
+  focused: bool,
+}
+
+enum InlineEditValue {
+  Text(String),
+  Number(ValText<u32, String>),
+}
+
+pub enum InlineEditAction {
+  Commit(DataField),
+  Cancel,
+  Noop,
+}
+
+impl InlineEdit {
+  pub fn new(key: &Keys, entry: &SfoEntry, num_display_mode: U32DisplayMode) -> Self {
+    let value = match entry.data {
+      DataField::Utf8String(text) => InlineEditValue::Text(text.clone()),
+      DataField::U32(val) => {
+        let mut num_input = u32_input();
+        num_input.replace_with(&num_display_mode.format(*val));
+        InlineEditValue::Number(num_input)
+      }
+    };
+
+    InlineEdit {
+      key: key.clone(),
+      value,
+      reserved_len: entry.index_table_entry.data_max_len,
+      null_terminated: !matches!(entry.index_table_entry.data_format, Format::Utf8Special),
+      err_msg: None,
+      focused: false,
+    }
+  }
+
+  pub fn show(&mut self, ui: &mut egui::Ui) -> InlineEditAction {
+    let multiline = self.key.is_multiline();
+    let response = ui
+      .vertical(|ui| {
+        let response = match &mut self.value {
+          InlineEditValue::Text(text) if multiline => ui
+            .text_edit_multiline(text)
+            .on_hover_text("Ctrl+Enter to save, Escape to cancel"),
+          InlineEditValue::Text(text) => ui
+            .text_edit_singleline(text)
+            .on_hover_text("Enter to save, Escape to cancel"),
+          InlineEditValue::Number(num_input) => ui
+            .text_edit_singleline(num_input)
+            .on_hover_text("Enter to save, Escape to cancel"),
+        };
+
+        if let Some(err_msg) = &self.err_msg {
+          ui.colored_label(ui.visuals().error_fg_color, err_msg);
+        }
+        response
+      })
+      .inner;
+
+    if !self.focused {
+      response.request_focus();
+      self.focused = true;
+    }
+
+    if ui.input(|input| input.key_pressed(egui::Key::Escape)) {
+      return InlineEditAction::Cancel;
+    }
+
+    let commit_pressed = ui.input(|input| {
+      input.key_pressed(egui::Key::Enter) && (!multiline || input.modifiers.command)
+    });
+    if commit_pressed && (response.has_focus() || response.lost_focus()) {
+      match self.data_field() {
+        Ok(data_field) => return InlineEditAction::Commit(data_field),
+        Err(err_msg) => {
+          self.err_msg = Some(err_msg);
+          response.request_focus();
+          return InlineEditAction::Noop;
+        }
+      }
+    }
+
+    if response.lost_focus() {
+      return InlineEditAction::Cancel;
+    }
+
+    InlineEditAction::Noop
+  }
+
+  fn data_field(&self) -> Result<DataField, String> {
+    match &self.value {
+      InlineEditValue::Text(text) => {
+        let text_len = text.len() as u32 + u32::from(self.null_terminated);
+        if let Some(max_len) = self.key.max_len()
+          && text_len > max_len
+        {
+          return Err(format!(
+            "Value takes {text_len} bytes which exceeds maximum of {max_len} bytes for key {}",
+            self.key
+          ));
+        }
+
+        if text_len > self.reserved_len {
+          return Err(format!(
+            "Value takes {text_len} bytes which exceeds reserved size of {} bytes, use Edit to grow it",
+            self.reserved_len
+          ));
+        }
+
+        Ok(DataField::Utf8String(text.clone()))
+      }
+      InlineEditValue::Number(num_input) => match num_input.get_val() {
+        Some(Ok(val)) => Ok(DataField::U32(*val)),
+        Some(Err(err)) => Err(err.clone()),
+        None => Err(String::from("Value is empty")),
+      },
+    }
+  }
+}
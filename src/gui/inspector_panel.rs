@@ -0,0 +1,92 @@
+use eframe::egui;
+
+use crate::sfo::Sfo;
+
+/// Side panel presenting the header and the index table of the sfo as they will be exported.
+pub fn show_inspector_panel(ctx: &egui::Context, sfo: &Sfo) {
+  egui::SidePanel::right("inspector_panel")
+    .resizable(true)
+    .show(ctx, |ui| {
+      egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::CollapsingHeader::new("Header")
+          .default_open(true)
+          .show(ui, |ui| header_grid(ui, sfo));
+
+        egui::CollapsingHeader::new("Index table")
+          .default_open(true)
+          .show(ui, |ui| index_table_grid(ui, sfo));
+      });
+    });
+}
+
+fn header_grid(ui: &mut egui::Ui, sfo: &Sfo) {
+  egui::Grid::new("inspector_header_grid")
+    .num_columns(2)
+    .spacing([20.0, 4.0])
+    .striped(true)
+    .show(ui, |ui| {
+      ui.label("Magic");
+      ui.monospace(format!("{:02X?}", sfo.magic));
+      ui.end_row();
+
+      ui.label("Version");
+      ui.monospace(format!(
+        "{} [{:#010X}]",
+        sfo.header.version_name(),
+        sfo.header.version
+      ));
+      ui.end_row();
+
+      ui.label("Key table start");
+      ui.monospace(format!("{:#010X}", sfo.header.key_table_start));
+      ui.end_row();
+
+      ui.label("Data table start");
+      ui.monospace(format!("{:#010X}", sfo.header.data_table_start));
+      ui.end_row();
+
+      ui.label("Table entries");
+      ui.monospace(sfo.header.table_entries.to_string());
+      ui.end_row();
+
+      ui.label("Key table padding");
+      ui.monospace(format!("{} bytes", sfo.padding));
+      ui.end_row();
+
+      ui.label("File size");
+      ui.monospace(format!("{} bytes", sfo.file_size()));
+      ui.end_row();
+    });
+}
+
+fn index_table_grid(ui: &mut egui::Ui, sfo: &Sfo) {
+  egui::Grid::new("inspector_index_table_grid")
+    .num_columns(6)
+    .spacing([12.0, 4.0])
+    .striped(true)
+    .show(ui, |ui| {
+      for header in [
+        "KEY",
+        "KEY OFFSET",
+        "FORMAT",
+        "LEN",
+        "MAX LEN",
+        "DATA OFFSET",
+      ] {
+        ui.strong(header);
+      }
+      ui.end_row();
+
+      for (key, entry) in sfo.iter() {
+        let index_table_entry = entry.index_table_entry;
+        ui.label(key.to_string());
+        ui.monospace(format!("{:#06X}", index_table_entry.key_offset));
+        ui.monospace(format!("0x04{:02X}", index_table_entry.data_format as u8))
+          .on_hover_text(index_table_entry.data_format.to_string());
+        ui.monospace(index_table_entry.data_len.to_string());
+        ui.monospace(index_table_entry.data_max_len.to_string());
+        ui.monospace(format!("{:#010X}", index_table_entry.data_offset));
+        ui.end_row();
+      }
+    });
+}
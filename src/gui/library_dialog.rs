@@ -0,0 +1,225 @@
+use std::path::PathBuf;
+
+use eframe::egui::{self};
+
+use crate::{
+  gui::{
+    file_dialogs::{load_library_csv_dialog, pick_library_folder_dialog, save_library_csv_dialog},
+    library_scan::LibraryScan,
+  },
+  library::{LibraryEntry, LibraryImportPlan},
+  sfo::keys::Keys,
+};
+
+const DEFAULT_KEYS: &str = "TITLE_ID, TITLE, APP_VER, CATEGORY";
+
+/// Exports values of all files in a library folder to csv and applies an edited csv back to them,
+/// after previewing its changes.
+pub struct LibraryDialog {
+  root: Option<PathBuf>,
+  scan: Option<LibraryScan<(Vec<LibraryEntry>, Vec<String>)>>,
+  entries: Vec<LibraryEntry>,
+  scan_issues: Vec<String>,
+  keys: String,
+  plan: Option<LibraryImportPlan>,
+  messages: Vec<String>,
+  pub saved_paths: Vec<PathBuf>,
+}
+
+impl LibraryDialog {
+  pub fn new() -> Self {
+    LibraryDialog {
+      root: None,
+      scan: None,
+      entries: Vec::new(),
+      scan_issues: Vec::new(),
+      keys: String::from(DEFAULT_KEYS),
+      plan: None,
+      messages: Vec::new(),
+      saved_paths: Vec::new(),
+    }
+  }
+
+  fn keys(&self) -> Vec<Keys> {
+    self
+      .keys
+      .split(',')
+      .map(str::trim)
+      .filter(|name| !name.is_empty())
+      .map(|name| {
+        name
+          .parse()
+          .unwrap_or_else(|_| Keys::Unknown(name.to_owned()))
+      })
+      .collect()
+  }
+
+  /// Takes the entries of the scan once it has finished.
+  fn finish_scan(&mut self) {
+    let Some(scan) = self.scan.take() else {
+      return;
+    };
+
+    match scan.poll() {
+      None => self.scan = Some(scan),
+      Some(Ok((entries, scan_issues))) => {
+        self.entries = entries;
+        self.scan_issues = scan_issues;
+        self.root = Some(scan.root);
+      }
+      Some(Err(err_msg)) => self.messages = vec![err_msg],
+    }
+  }
+
+  pub fn show(&mut self, ctx: &eframe::egui::Context) -> Option<bool> {
+    self.finish_scan();
+
+    egui::Modal::new(egui::Id::new("library_dialog"))
+      .show(ctx, |ui| {
+        ui.set_width(600.0);
+        ui.heading("Library csv");
+
+        ui.horizontal(|ui| {
+          let choose_btn = ui.add_enabled(self.scan.is_none(), egui::Button::new("Choose folder"));
+          if choose_btn.clicked() {
+            match pick_library_folder_dialog() {
+              Ok(root) => {
+                self.root = None;
+                self.entries.clear();
+                self.scan_issues.clear();
+                self.scan = Some(LibraryScan::start(ctx, root, |entries, issues| {
+                  (entries, issues)
+                }));
+              }
+              Err(err_msg) => self.messages = vec![err_msg],
+            }
+          }
+
+          match (&self.scan, &self.root) {
+            (Some(scan), _) => scan.show_progress(ui),
+            (None, Some(root)) => {
+              ui.label(format!(
+                "{} ({} files found)",
+                root.display(),
+                self.entries.len()
+              ));
+            }
+            (None, None) => {
+              ui.label("No folder chosen");
+            }
+          };
+        });
+        for issue in &self.scan_issues {
+          ui.colored_label(ui.visuals().warn_fg_color, format!("Skipped {issue}"));
+        }
+
+        ui.horizontal(|ui| {
+          ui.label("Keys");
+          ui.text_edit_singleline(&mut self.keys)
+            .on_hover_text("Comma-separated keys exported as columns");
+        });
+
+        ui.horizontal(|ui| {
+          let export_btn = ui
+            .add_enabled(!self.entries.is_empty(), egui::Button::new("Export csv"))
+            .on_disabled_hover_text("Choose a folder with .sfo, .pbp, .iso or .sfx files first");
+          if export_btn.clicked() {
+            self.messages = match save_library_csv_dialog(&self.entries, &self.keys()) {
+              Ok(_) => vec![format!("Exported {} files", self.entries.len())],
+              Err(err_msg) => vec![err_msg],
+            };
+          }
+
+          let import_btn = ui
+            .button("Import csv")
+            .on_hover_text("Preview changes of an edited csv before applying them");
+          if import_btn.clicked() {
+            match load_library_csv_dialog() {
+              Ok(plan) => {
+                self.plan = Some(plan);
+                self.messages.clear();
+              }
+              Err(err_msg) => self.messages = vec![err_msg],
+            }
+          }
+        });
+        ui.separator();
+
+        if let Some(plan) = &self.plan {
+          Self::show_plan(ui, plan);
+        }
+
+        for message in &self.messages {
+          ui.label(message);
+        }
+        ui.separator();
+
+        ui.horizontal(|ui| {
+          let apply_btn = ui
+            .add_enabled(
+              self
+                .plan
+                .as_ref()
+                .is_some_and(|plan| !plan.files.is_empty()),
+              egui::Button::new("Apply changes"),
+            )
+            .on_hover_text(
+              "Every changed file is copied to a .bak file first unless one exists, so the backup keeps the file from before the first change",
+            )
+            .on_disabled_hover_text("Import a csv with changes first");
+          if apply_btn.clicked()
+            && let Some(plan) = self.plan.take()
+          {
+            let (saved, errors) = plan.apply();
+            self.messages = vec![format!("Saved {} files", saved.len())];
+            self.messages.extend(errors);
+            self.saved_paths.extend(saved);
+          }
+
+          let close_btn = ui.button("Close");
+          if close_btn.clicked() {
+            return Some(false);
+          }
+
+          None
+        })
+        .inner
+      })
+      .inner
+  }
+
+  fn show_plan(ui: &mut egui::Ui, plan: &LibraryImportPlan) {
+    ui.strong(format!(
+      "{} changes in {} files",
+      plan.edits_count(),
+      plan.files.len()
+    ));
+    for issue in &plan.issues {
+      ui.colored_label(ui.visuals().warn_fg_color, format!("Skipped {issue}"));
+    }
+
+    egui::ScrollArea::vertical()
+      .max_height(300.0)
+      .show(ui, |ui| {
+        egui::Grid::new("library_plan_grid")
+          .striped(true)
+          .show(ui, |ui| {
+            ui.strong("File");
+            ui.strong("Key");
+            ui.strong("Old value");
+            ui.strong("New value");
+            ui.end_row();
+
+            for file in &plan.files {
+              for edit in &file.edits {
+                ui.label(file.path.to_string_lossy());
+                ui.label(edit.key.to_string());
+                ui.label(edit.old.to_string());
+                ui.label(edit.new.to_string());
+                ui.end_row();
+              }
+            }
+          });
+      });
+  }
+}
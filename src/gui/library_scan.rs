@@ -0,0 +1,77 @@
+use std::{
+  path::PathBuf,
+  sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+    mpsc::{self, Receiver, TryRecvError},
+  },
+  thread,
+};
+
+use eframe::egui::{self};
+
+use crate::library::{LibraryEntry, scan_library_with_progress};
+
+/// Scan of a library folder running on a background thread, so that the window keeps responding
+/// while large folders are loaded.
+pub struct LibraryScan<T> {
+  pub root: PathBuf,
+  files: Arc<AtomicUsize>,
+  receiver: Receiver<T>,
+}
+
+impl<T> LibraryScan<T>
+where
+  T: Send + 'static,
+{
+  /// Starts scanning the root. Found entries and issues are passed to `finish`, which runs on the
+  /// background thread as well.
+  pub fn start<F>(ctx: &egui::Context, root: PathBuf, finish: F) -> Self
+  where
+    F: FnOnce(Vec<LibraryEntry>, Vec<String>) -> T + Send + 'static,
+  {
+    let files = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+    let thread_root = root.clone();
+    let thread_files = files.clone();
+    let ctx = ctx.clone();
+    thread::spawn(move || {
+      let (entries, issues) = scan_library_with_progress(&thread_root, |_| {
+        thread_files.fetch_add(1, Ordering::Relaxed);
+        ctx.request_repaint();
+      });
+      // The receiver is gone when the scan has been cancelled.
+      let _ = sender.send(finish(entries, issues));
+      ctx.request_repaint();
+    });
+
+    LibraryScan {
+      root,
+      files,
+      receiver,
+    }
+  }
+
+  /// Result of the scan once it has finished.
+  pub fn poll(&self) -> Option<Result<T, String>> {
+    match self.receiver.try_recv() {
+      Ok(result) => Some(Ok(result)),
+      Err(TryRecvError::Empty) => None,
+      Err(TryRecvError::Disconnected) => Some(Err(format!(
+        "scanning {} has stopped unexpectedly",
+        self.root.display()
+      ))),
+    }
+  }
+
+  pub fn show_progress(&self, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+      ui.spinner();
+      ui.label(format!(
+        "Scanning {} ({} files found)",
+        self.root.display(),
+        self.files.load(Ordering::Relaxed)
+      ));
+    });
+  }
+}
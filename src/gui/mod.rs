@@ -1,28 +1,172 @@
 use std::{
   borrow::Cow,
+  collections::HashMap,
   path::{Path, PathBuf},
 };
 
 use crate::{
+  assets::{Asset, save_asset},
+  file_ops::{is_iso_path, is_pbp_path, is_sfx_path, load_icon_file, load_sfo_file, save_iso_file},
+  game_folder::{GameFolder, GameFolderLayout},
   gui::{
+    asset_tool_dialog::AssetToolDialog,
+    assets_panel::AssetsPanel,
+    convert_type_dialog::ConvertTypeDialog,
     delete_entry_dialog::DeleteEntryDialog,
+    entries_filter::{EntriesFilter, FilteredEntry},
     entry_update_modal::EntryUpdateModal,
-    file_dialogs::{load_sfo_dialog, save_sfo_dialog},
-    file_ops::load_sfo_file,
+    expert_layout_dialog::ExpertLayoutDialog,
+    file_dialogs::{
+      load_sfo_dialog, load_titles_csv_dialog, open_game_folder_dialog, pick_library_folder_dialog,
+      save_pbp_dialog, save_report_dialog, save_sfo_dialog, save_sfx_dialog,
+      save_titles_csv_dialog,
+    },
+    inline_edit::{InlineEdit, InlineEditAction},
+    inspector_panel::show_inspector_panel,
+    library_dialog::LibraryDialog,
+    library_scan::LibraryScan,
+    new_sfo_dialog::NewSfoDialog,
+    report_dialog::{ReportDialog, ReportScope},
+    save_summary_dialog::SaveSummaryDialog,
+    titles_grid::{TitlesGrid, TitlesGridAction},
+    u32_display_mode::U32DisplayMode,
+    xmb_preview::XmbPreview,
+  },
+  report::{ReportFile, ReportFormat, render_report},
+  sfo::{
+    Sfo,
+    diff::{EntryChange, diff, revert_entry},
+    keys::Keys,
+    languages::set_localized_title,
+    mapping::DataField,
+    validation::{ValidationIssue, validate},
   },
-  sfo::Sfo,
 };
 use eframe::egui::{self, Id};
+use strum::IntoEnumIterator;
 
+mod asset_tool_dialog;
+mod assets_panel;
+mod convert_type_dialog;
 mod delete_entry_dialog;
+mod entries_filter;
 mod entry_update_modal;
+mod expert_layout_dialog;
 mod file_dialogs;
-mod file_ops;
+mod inline_edit;
+mod inspector_panel;
+mod library_dialog;
+mod library_scan;
+mod new_sfo_dialog;
+mod report_dialog;
+mod save_summary_dialog;
+mod titles_grid;
+mod u32_display_mode;
+mod xmb_preview;
 
 struct LoadedSfo {
   sfo: Sfo,
-  path: PathBuf,
+  original: Sfo,
+  path: Option<PathBuf>,
+  game_folder: Option<GameFolder>,
+  /// File names of the assets present in the game folder, refreshed after assets are saved.
+  assets: Vec<String>,
   modified: bool,
+  validation_issues: Vec<ValidationIssue>,
+  changes: Vec<(Keys, EntryChange)>,
+}
+
+impl LoadedSfo {
+  fn new(sfo: Sfo, path: Option<PathBuf>) -> Self {
+    let mut loaded_sfo = LoadedSfo {
+      original: sfo.clone(),
+      sfo,
+      path,
+      game_folder: None,
+      assets: Vec::new(),
+      modified: false,
+      validation_issues: Vec::new(),
+      changes: Vec::new(),
+    };
+    loaded_sfo.refresh();
+    loaded_sfo
+  }
+
+  /// Recomputes validation issues and changes against the original file. Has to be called after
+  /// every change of the sfo, instead of recomputing them on every frame.
+  fn refresh(&mut self) {
+    self.validation_issues = validate(&self.sfo);
+    self.changes = diff(&self.original, &self.sfo)
+      .into_iter()
+      .map(|entry_diff| (entry_diff.key.clone(), entry_diff.change))
+      .collect();
+    self.modified = !self.changes.is_empty()
+      || self.sfo.header.version != self.original.header.version
+      || self.sfo.padding != self.original.padding;
+  }
+
+  fn change(&self, key: &Keys) -> Option<EntryChange> {
+    self
+      .changes
+      .iter()
+      .find(|(changed_key, _)| changed_key == key)
+      .map(|(_, change)| *change)
+  }
+
+  /// Folder in which assets of the loaded file are stored - the opened game folder, or the folder
+  /// of a file loaded on its own, treated as a PS3 game folder. `None` when the file is not stored
+  /// directly in a folder.
+  fn assets_folder(&self) -> Option<GameFolder> {
+    if let Some(game_folder) = &self.game_folder {
+      return Some(game_folder.clone());
+    }
+
+    let path = self
+      .path
+      .as_ref()
+      .filter(|path| !is_pbp_path(path) && !is_iso_path(path))?;
+    Some(GameFolder {
+      root: path.parent()?.to_path_buf(),
+      layout: GameFolderLayout::Ps3Game,
+      sfo_path: path.clone(),
+    })
+  }
+
+  fn from_game_folder(game_folder: GameFolder) -> Result<Self, String> {
+    let sfo = load_sfo_file(&game_folder.sfo_path)?;
+    let sfo_path = game_folder.sfo_path.clone();
+    Ok(LoadedSfo::new(sfo, Some(sfo_path)).with_game_folder(Some(game_folder)))
+  }
+
+  fn with_game_folder(mut self, game_folder: Option<GameFolder>) -> Self {
+    self.game_folder = game_folder;
+    self.refresh_assets();
+    self
+  }
+
+  /// Lists the assets of the game folder again, has to be called after assets are written.
+  fn refresh_assets(&mut self) {
+    self.assets = self.game_folder.as_ref().map_or(Vec::new(), |game_folder| {
+      game_folder
+        .assets()
+        .iter()
+        .filter_map(|asset| asset.file_name())
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .collect()
+    });
+  }
+}
+
+enum GridAction {
+  CommitInlineEdit(Keys, DataField),
+  Revert(Keys),
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum View {
+  #[default]
+  Entries,
+  Titles,
 }
 
 pub struct GuiApp {
@@ -30,9 +174,29 @@ pub struct GuiApp {
   sfo: Option<LoadedSfo>,
   entry_update_modal: Option<EntryUpdateModal>,
   delete_entry_dialog: Option<DeleteEntryDialog>,
+  convert_type_dialog: Option<ConvertTypeDialog>,
+  save_summary_dialog: Option<SaveSummaryDialog>,
+  expert_layout_dialog: Option<ExpertLayoutDialog>,
+  new_sfo_dialog: Option<NewSfoDialog>,
+  asset_tool_dialog: Option<AssetToolDialog>,
+  library_dialog: Option<LibraryDialog>,
+  report_dialog: Option<ReportDialog>,
+  /// Library report rendered in the background, with its format and the skipped files.
+  report_scan: Option<LibraryScan<(ReportFormat, String, Vec<String>)>>,
+  view: View,
+  show_inspector: bool,
+  show_xmb_preview: bool,
+  entries_filter: EntriesFilter,
+  inline_edit: Option<InlineEdit>,
+  titles_grid: TitlesGrid,
+  assets_panel: AssetsPanel,
+  xmb_preview: XmbPreview,
+  u32_column_display_mode: Option<U32DisplayMode>,
+  u32_display_modes: HashMap<Keys, U32DisplayMode>,
 }
 
 const NO_SFO_FILE_MSG: &str = "No .sfo file has been provided";
+const NEW_SFO_FILE_MSG: &str = "New file (not saved yet)";
 
 impl GuiApp {
   pub fn new<T>(_cc: &eframe::CreationContext<'_>, path: Option<&T>) -> Self
@@ -41,7 +205,12 @@ impl GuiApp {
   {
     let mut err_msg: Option<String> = None;
     let sfo = path.and_then(|path| {
-      load_sfo_file(path.as_ref()).map_or_else(
+      let loaded_sfo = match path.as_ref().is_dir() {
+        true => GameFolder::open(path).and_then(LoadedSfo::from_game_folder),
+        false => load_sfo_file(path.as_ref())
+          .map(|sfo| LoadedSfo::new(sfo, Some(PathBuf::from(path.as_ref())))),
+      };
+      loaded_sfo.map_or_else(
         |err| {
           err_msg = Some(format!(
             "could not load sfo file with path {} provided in \"input-file\" argument: {err}",
@@ -49,13 +218,7 @@ impl GuiApp {
           ));
           None
         },
-        |sfo| {
-          Some(LoadedSfo {
-            sfo,
-            path: PathBuf::from(path.as_ref()),
-            modified: false,
-          })
-        },
+        Some,
       )
     });
 
@@ -64,25 +227,127 @@ impl GuiApp {
       err_msg,
       entry_update_modal: None,
       delete_entry_dialog: None,
+      convert_type_dialog: None,
+      save_summary_dialog: None,
+      expert_layout_dialog: None,
+      new_sfo_dialog: None,
+      asset_tool_dialog: None,
+      library_dialog: None,
+      report_dialog: None,
+      report_scan: None,
+      view: Default::default(),
+      show_inspector: false,
+      show_xmb_preview: false,
+      entries_filter: Default::default(),
+      inline_edit: None,
+      titles_grid: Default::default(),
+      assets_panel: Default::default(),
+      xmb_preview: Default::default(),
+      u32_column_display_mode: None,
+      u32_display_modes: HashMap::new(),
     }
   }
 
   fn show_header(&mut self, ctx: &egui::Context) {
     egui::TopBottomPanel::top("header_panel").show(ctx, |ui| {
       ui.horizontal(|ui| {
+        let new_sfo_btn = ui.button("New .sfo file");
+        if new_sfo_btn.clicked() {
+          self.new_sfo_dialog = Some(NewSfoDialog::new());
+        }
+
         let load_sfo_btn = ui.button("Load .sfo file");
         if load_sfo_btn.clicked() {
           self.show_load_sfo_dialog(ctx);
         }
 
+        let open_game_folder_btn = ui.button("Open game folder");
+        if open_game_folder_btn.clicked() {
+          self.show_open_game_folder_dialog(ctx);
+        }
+
         let save_sfo_btn = ui
           .add_enabled(
             self.sfo.as_ref().is_some_and(|sfo| sfo.modified),
             egui::Button::new("Save .sfo file"),
           )
           .on_disabled_hover_text("The loaded file has not been modified");
-        if save_sfo_btn.clicked() {
-          self.show_save_sfo_dialog();
+        if save_sfo_btn.clicked()
+          && let Some(loaded_sfo) = &self.sfo
+        {
+          self.save_summary_dialog = Some(SaveSummaryDialog::new(
+            &loaded_sfo.original,
+            &loaded_sfo.sfo,
+            loaded_sfo.path.as_deref(),
+          ));
+        }
+
+        let export_sfx_btn = ui
+          .add_enabled(self.sfo.is_some(), egui::Button::new("Export param.sfx"))
+          .on_hover_text("Write the loaded file as param.sfx XML of the PS3 SDK")
+          .on_disabled_hover_text("No file has been loaded");
+        if export_sfx_btn.clicked()
+          && let Some(loaded_sfo) = &self.sfo
+          && let Err(err_msg) = save_sfx_dialog(&loaded_sfo.sfo)
+        {
+          self.err_msg = Some(err_msg);
+        }
+
+        let revert_all_btn = ui
+          .add_enabled(
+            self.sfo.as_ref().is_some_and(|sfo| sfo.modified),
+            egui::Button::new("Revert all"),
+          )
+          .on_hover_text("Reload the file from disk, discarding all changes")
+          .on_disabled_hover_text("The loaded file has not been modified");
+        if revert_all_btn.clicked() {
+          self.revert_all();
+        }
+
+        ui.separator();
+        ui.selectable_value(&mut self.view, View::Entries, "Entries");
+        ui.selectable_value(&mut self.view, View::Titles, "Titles");
+        ui.separator();
+        ui.toggle_value(&mut self.show_inspector, "Inspector")
+          .on_hover_text("Show the header and the index table");
+        ui.toggle_value(&mut self.show_xmb_preview, "XMB preview")
+          .on_hover_text("Show how the entry may look on the XMB");
+
+        let assets_folder = self.sfo.as_ref().and_then(LoadedSfo::assets_folder);
+        let asset_tool_btn = ui
+          .add_enabled(assets_folder.is_some(), egui::Button::new("Replace asset"))
+          .on_hover_text("Convert an image to ICON0, PIC0 or PIC1 next to the loaded file")
+          .on_disabled_hover_text("The loaded file is not stored in a folder");
+        if asset_tool_btn.clicked()
+          && let Some(assets_folder) = assets_folder
+        {
+          self.asset_tool_dialog = Some(AssetToolDialog::new(
+            assets_folder.assets_dir().to_path_buf(),
+            assets_folder.layout,
+          ));
+        }
+
+        let library_btn = ui
+          .button("Library csv")
+          .on_hover_text("Export or edit values of all files in a folder through csv");
+        if library_btn.clicked() {
+          self.library_dialog = Some(LibraryDialog::new());
+        }
+
+        let report_btn = ui
+          .button("Export report")
+          .on_hover_text("Write the loaded file or a whole library as Markdown or HTML");
+        if report_btn.clicked() {
+          self.report_dialog = Some(ReportDialog::new(self.sfo.is_some()));
+        }
+
+        let expert_layout_btn = ui
+          .button("Expert layout")
+          .on_hover_text("Edit reserved sizes, header version and key table padding directly");
+        if expert_layout_btn.clicked()
+          && let Some(loaded_sfo) = &self.sfo
+        {
+          self.expert_layout_dialog = Some(ExpertLayoutDialog::new(&loaded_sfo.sfo));
         }
       });
 
@@ -91,19 +356,97 @@ impl GuiApp {
         self
           .sfo
           .as_ref()
-          .map_or(Cow::from(NO_SFO_FILE_MSG), |sfo| sfo.path.to_string_lossy())
+          .map_or(Cow::from(NO_SFO_FILE_MSG), |sfo| sfo
+            .path
+            .as_ref()
+            .map_or(Cow::from(NEW_SFO_FILE_MSG), |path| path
+              .to_string_lossy()))
       ));
+      if let Some(loaded_sfo) = &self.sfo {
+        ui.label(format!(
+          "PSF version: {}",
+          loaded_sfo.sfo.header.version_name()
+        ));
+      }
+      if let Some(loaded_sfo) = &self.sfo
+        && let Some(game_folder) = &loaded_sfo.game_folder
+      {
+        ui.label(format!(
+          "Game folder: {} ({})",
+          game_folder.root.to_string_lossy(),
+          game_folder.layout
+        ));
+        ui.label(format!(
+          "Assets: {}",
+          match loaded_sfo.assets.is_empty() {
+            true => String::from("none"),
+            false => loaded_sfo.assets.join(", "),
+          }
+        ));
+      }
     });
   }
 
-  fn show_loaded_file(&mut self, ctx: &egui::Context, loaded_sfo: &LoadedSfo) {
+  fn show_loaded_file(&mut self, ctx: &egui::Context, loaded_sfo: &mut LoadedSfo) {
     egui::CentralPanel::default().show(ctx, |ui| {
-      egui::ScrollArea::both().show(ui, |ui| {
-        self.mapping_entries_grid(ui, &loaded_sfo.sfo);
+      egui::ScrollArea::both().show(ui, |ui| match self.view {
+        View::Entries => {
+          self.entries_filter.show(ui);
+          ui.separator();
+          let result = match self.mapping_entries_grid(ui, loaded_sfo) {
+            Some(GridAction::CommitInlineEdit(key, data_field)) => {
+              loaded_sfo.sfo.edit(&key, data_field)
+            }
+            Some(GridAction::Revert(key)) => {
+              revert_entry(&loaded_sfo.original, &mut loaded_sfo.sfo, &key)
+            }
+            None => return,
+          };
+          match result {
+            Ok(_) => loaded_sfo.refresh(),
+            Err(err_msg) => self.err_msg = Some(err_msg),
+          }
+        }
+        View::Titles => {
+          if let Some(action) = self.titles_grid.show(ui, &loaded_sfo.sfo) {
+            self.handle_titles_grid_action(loaded_sfo, action);
+          }
+        }
       });
     });
   }
 
+  fn handle_titles_grid_action(&mut self, loaded_sfo: &mut LoadedSfo, action: TitlesGridAction) {
+    let titles = match action {
+      TitlesGridAction::Save(language, title) => vec![(language, Some(title))],
+      TitlesGridAction::Clear(language) => vec![(language, None)],
+      TitlesGridAction::ImportCsv => match load_titles_csv_dialog() {
+        Ok(titles) => {
+          self.titles_grid.reset();
+          titles
+        }
+        Err(err_msg) => {
+          self.err_msg = Some(format!("could not import titles: {err_msg}"));
+          return;
+        }
+      },
+      TitlesGridAction::ExportCsv => {
+        if let Err(err_msg) = save_titles_csv_dialog(&loaded_sfo.sfo) {
+          self.err_msg = Some(format!("could not export titles: {err_msg}"));
+        }
+        return;
+      }
+    };
+
+    for (language, title) in titles {
+      if let Err(err_msg) = set_localized_title(&mut loaded_sfo.sfo, language, title) {
+        self.err_msg = Some(err_msg);
+        break;
+      }
+    }
+    loaded_sfo.refresh();
+  }
+
   fn show_no_file_loaded_info(&mut self, ctx: &egui::Context) {
     egui::CentralPanel::default().show(ctx, |ui| {
       ui.with_layout(
@@ -115,6 +458,26 @@ impl GuiApp {
           if upload_link.clicked() {
             self.show_load_sfo_dialog(ctx);
           }
+
+          let open_game_folder_link = ui.link("Click here to open a game folder");
+          if open_game_folder_link.clicked() {
+            self.show_open_game_folder_dialog(ctx);
+          }
+
+          let new_sfo_link = ui.link("Click here to create a new .sfo file");
+          if new_sfo_link.clicked() {
+            self.new_sfo_dialog = Some(NewSfoDialog::new());
+          }
+
+          let library_link = ui.link("Click here to export or edit a library through csv");
+          if library_link.clicked() {
+            self.library_dialog = Some(LibraryDialog::new());
+          }
+
+          let report_link = ui.link("Click here to export a report of a library");
+          if report_link.clicked() {
+            self.report_dialog = Some(ReportDialog::new(false));
+          }
         },
       );
     });
@@ -133,7 +496,55 @@ impl GuiApp {
     }
   }
 
-  fn mapping_entries_grid(&mut self, ui: &mut eframe::egui::Ui, sfo: &Sfo) {
+  fn u32_display_mode(&self, key: &Keys) -> U32DisplayMode {
+    self
+      .u32_display_modes
+      .get(key)
+      .copied()
+      .or(self.u32_column_display_mode)
+      .unwrap_or_else(|| U32DisplayMode::for_key(key))
+  }
+
+  fn u32_column_display_mode_combo_box(&mut self, ui: &mut egui::Ui) {
+    let selected_text = self
+      .u32_column_display_mode
+      .map_or(String::from("auto"), |mode| mode.to_string());
+
+    egui::ComboBox::from_id_salt("u32_column_display_mode")
+      .selected_text(format!("Numbers: {selected_text}"))
+      .show_ui(ui, |ui| {
+        if ui
+          .selectable_label(self.u32_column_display_mode.is_none(), "auto")
+          .clicked()
+        {
+          self.u32_column_display_mode = None;
+          self.u32_display_modes.clear();
+        }
+
+        for mode in U32DisplayMode::iter() {
+          if ui
+            .selectable_label(self.u32_column_display_mode == Some(mode), mode.to_string())
+            .clicked()
+          {
+            self.u32_column_display_mode = Some(mode);
+            self.u32_display_modes.clear();
+          }
+        }
+      });
+  }
+
+  fn mapping_entries_grid(
+    &mut self,
+    ui: &mut eframe::egui::Ui,
+    loaded_sfo: &LoadedSfo,
+  ) -> Option<GridAction> {
+    let sfo = &loaded_sfo.sfo;
+    let mut grid_action: Option<GridAction> = None;
+    let validation_issues = &loaded_sfo.validation_issues;
+    for issue in validation_issues.iter().filter(|issue| issue.key.is_none()) {
+      ui.colored_label(ui.visuals().warn_fg_color, issue.to_string());
+    }
+
     egui::Grid::new("mapping_grid")
       .num_columns(3)
       .min_col_width(10.0)
@@ -143,11 +554,14 @@ impl GuiApp {
       .show(ui, |ui| {
         let add_btn = ui.button("Add");
         if add_btn.clicked() {
-          self.entry_update_modal = Some(EntryUpdateModal::new_add_entry_modal());
+          self.entry_update_modal = Some(EntryUpdateModal::new_add_entry_modal(sfo));
         }
 
         ui.label("KEY");
-        ui.label("DATA");
+        ui.horizontal(|ui| {
+          ui.label("DATA");
+          self.u32_column_display_mode_combo_box(ui);
+        });
         ui.end_row();
 
         ui.label("");
@@ -156,6 +570,25 @@ impl GuiApp {
         ui.end_row();
 
         for (key, entry) in sfo.iter() {
+          let change = loaded_sfo.change(key);
+          let issues: Vec<&ValidationIssue> = validation_issues
+            .iter()
+            .filter(|issue| issue.key.as_ref() == Some(key))
+            .collect();
+          let values = match entry.data {
+            DataField::U32(val) => vec![val.to_string(), self.u32_display_mode(key).format(*val)],
+            DataField::Utf8String(text) => vec![text.clone()],
+          };
+          let filtered_entry = FilteredEntry {
+            key,
+            values: &values,
+            modified: change.is_some(),
+            invalid: issues.iter().any(|issue| issue.is_error()),
+          };
+          if !self.entries_filter.matches(&filtered_entry) {
+            continue;
+          }
+
           ui.horizontal(|ui| {
             let del_btn = ui.button("Del");
             if del_btn.clicked() {
@@ -164,24 +597,279 @@ impl GuiApp {
 
             let edit_btn = ui.button("Edit");
             if edit_btn.clicked() {
-              self.entry_update_modal = Some(EntryUpdateModal::new_update_entry_modal(key, &entry));
+              self.entry_update_modal = Some(EntryUpdateModal::new_update_entry_modal(
+                sfo,
+                key,
+                &entry,
+                self.u32_display_mode(key),
+              ));
+            }
+
+            let convert_btn = ui
+              .button("Type")
+              .on_hover_text("Convert the value to the other data type");
+            if convert_btn.clicked() {
+              self.convert_type_dialog = Some(ConvertTypeDialog::new(sfo, key.clone()));
+            }
+
+            if let Some(change) = change
+              && self.entry_change_marker(ui, change).clicked()
+            {
+              grid_action = Some(GridAction::Revert(key.clone()));
             }
           });
 
-          ui.label(key.to_string())
-            .on_hover_text(entry.index_table_entry.to_string());
-          ui.label(entry.data.to_string());
+          let key_label = match issues.is_empty() {
+            true => ui.label(key.to_string()),
+            false => ui.colored_label(ui.visuals().warn_fg_color, key.to_string()),
+          };
+          key_label.on_hover_text(
+            std::iter::once(entry.index_table_entry.to_string())
+              .chain(issues.iter().map(|issue| issue.to_string()))
+              .collect::<Vec<String>>()
+              .join("\n"),
+          );
+
+          if let Some(inline_edit) = &mut self.inline_edit
+            && inline_edit.key == *key
+          {
+            match inline_edit.show(ui) {
+              InlineEditAction::Commit(data_field) => {
+                grid_action = Some(GridAction::CommitInlineEdit(key.clone(), data_field));
+                self.inline_edit = None;
+              }
+              InlineEditAction::Cancel => self.inline_edit = None,
+              InlineEditAction::Noop => {}
+            }
+            ui.end_row();
+            continue;
+          }
+
+          let data_label = match entry.data {
+            DataField::U32(val) => {
+              let display_mode = self.u32_display_mode(key);
+              ui.horizontal(|ui| {
+                let mode_btn = ui
+                  .small_button(display_mode.to_string())
+                  .on_hover_text("Switch number display mode of the entry");
+                if mode_btn.clicked() {
+                  self
+                    .u32_display_modes
+                    .insert(key.clone(), display_mode.next());
+                }
+                ui.add(egui::Label::new(display_mode.format(*val)).sense(egui::Sense::click()))
+              })
+              .inner
+            }
+            DataField::Utf8String(_) if key.is_multiline() => ui.add(
+              egui::Label::new(entry.data.to_string())
+                .wrap()
+                .sense(egui::Sense::click()),
+            ),
+            DataField::Utf8String(_) => {
+              ui.add(egui::Label::new(entry.data.to_string()).sense(egui::Sense::click()))
+            }
+          };
+          if data_label
+            .on_hover_text("Double-click to edit the value")
+            .double_clicked()
+          {
+            self.inline_edit = Some(InlineEdit::new(key, &entry, self.u32_display_mode(key)));
+          }
+          ui.end_row();
+        }
+
+        let deleted_keys = loaded_sfo
+          .changes
+          .iter()
+          .filter(|(_, change)| *change == EntryChange::Deleted)
+          .map(|(key, _)| key);
+        for key in deleted_keys {
+          let Some(original_data) = loaded_sfo.original.get(key) else {
+            continue;
+          };
+          let values = [original_data.to_string()];
+          let filtered_entry = FilteredEntry {
+            key,
+            values: &values,
+            modified: true,
+            invalid: false,
+          };
+          if !self.entries_filter.matches(&filtered_entry) {
+            continue;
+          }
+
+          ui.horizontal(|ui| {
+            if self.entry_change_marker(ui, EntryChange::Deleted).clicked() {
+              grid_action = Some(GridAction::Revert(key.clone()));
+            }
+          });
+          ui.label(egui::RichText::new(key.to_string()).strikethrough());
+          ui.label(egui::RichText::new(original_data.to_string()).strikethrough());
           ui.end_row();
         }
       });
+
+    grid_action
   }
 
+  /// Shows marker of the entry change followed by a revert button, returning response of the
+  /// button.
+  fn entry_change_marker(&self, ui: &mut egui::Ui, change: EntryChange) -> egui::Response {
+    let (marker, color) = match change {
+      EntryChange::Added => ("added", egui::Color32::GREEN),
+      EntryChange::Changed => ("changed", ui.visuals().warn_fg_color),
+      EntryChange::Deleted => ("deleted", ui.visuals().error_fg_color),
+    };
+    ui.colored_label(color, marker);
+    ui.button("Revert")
+      .on_hover_text("Restore the entry to its state in the loaded file")
+  }
+
+  fn revert_all(&mut self) {
+    let Some(loaded_sfo) = &self.sfo else {
+      return;
+    };
+
+    let reloaded_sfo = match &loaded_sfo.path {
+      Some(path) => load_sfo_file(path),
+      None => Ok(loaded_sfo.original.clone()),
+    };
+    match reloaded_sfo {
+      Ok(sfo) => {
+        self.sfo = Some(
+          LoadedSfo::new(sfo, loaded_sfo.path.clone())
+            .with_game_folder(loaded_sfo.game_folder.clone()),
+        );
+        self.titles_grid.reset();
+        self.inline_edit = None;
+      }
+      Err(err_msg) => {
+        self.err_msg = Some(format!("could not revert all changes: {err_msg}"));
+      }
+    }
+  }
+
+  fn export_report(&mut self, ctx: &egui::Context, format: ReportFormat, scope: ReportScope) {
+    let result = match scope {
+      ReportScope::LoadedFile => match &self.sfo {
+        Some(loaded_sfo) => {
+          let file = ReportFile {
+            path: loaded_sfo.path.as_deref(),
+            sfo: &loaded_sfo.sfo,
+            icon: loaded_sfo.path.as_ref().and_then(load_icon_file),
+          };
+          save_report_dialog(&render_report(&[file], format), format)
+        }
+        None => Err(String::from("No file has been loaded")),
+      },
+      ReportScope::Library => pick_library_folder_dialog().map(|root| {
+        self.report_scan = Some(LibraryScan::start(ctx, root, move |entries, issues| {
+          let files: Vec<ReportFile> = entries
+            .iter()
+            .map(|entry| ReportFile {
+              path: Some(&entry.path),
+              sfo: &entry.sfo,
+              icon: load_icon_file(&entry.path),
+            })
+            .collect();
+          (format, render_report(&files, format), issues)
+        }));
+      }),
+    };
+
+    if let Err(err_msg) = result {
+      self.err_msg = Some(err_msg);
+    }
+  }
+
+  /// Shows progress of the library report and saves it once rendered.
+  fn show_report_scan(&mut self, ctx: &egui::Context) {
+    let Some(scan) = self.report_scan.take() else {
+      return;
+    };
+
+    let result = match scan.poll() {
+      None => {
+        let cancel = egui::Modal::new(egui::Id::new("report_scan"))
+          .show(ctx, |ui| {
+            ui.heading("Export report");
+            scan.show_progress(ui);
+            ui.button("Cancel").clicked()
+          })
+          .inner;
+        if !cancel {
+          self.report_scan = Some(scan);
+        }
+        return;
+      }
+      Some(result) => result,
+    };
+
+    let result = result.and_then(|(format, report, issues)| {
+      save_report_dialog(&report, format)?;
+      match issues.is_empty() {
+        true => Ok(()),
+        false => Err(format!(
+          "report has been saved, skipped files:\n{}",
+          issues.join("\n")
+        )),
+      }
+    });
+    if let Err(err_msg) = result {
+      self.err_msg = Some(err_msg);
+    }
+  }
+
+  /// Reloads the loaded file when it is one of the paths saved outside of the editor.
+  fn reload_saved_file(&mut self, saved_paths: &[PathBuf]) {
+    let Some(loaded_sfo) = &self.sfo else {
+      return;
+    };
+    if !loaded_sfo
+      .path
+      .as_ref()
+      .is_some_and(|path| saved_paths.contains(path))
+    {
+      return;
+    }
+
+    match loaded_sfo.modified {
+      true => {
+        self.err_msg = Some(String::from(
+          "the loaded file has been changed on disk, use \"Revert all\" to reload it",
+        ))
+      }
+      false => self.revert_all(),
+    }
+  }
+
+  /// Saves the loaded file and, once saved, treats the written file as the loaded one - the
+  /// saved sfo becomes the original against which later changes are compared.
   fn show_save_sfo_dialog(&mut self) {
-    if let Some(sfo) = &self.sfo {
-      let result = save_sfo_dialog(&sfo.sfo);
-      if let Err(err_msg) = result {
-        self.err_msg = Some(err_msg);
+    let Some(loaded_sfo) = &self.sfo else {
+      return;
+    };
+
+    let result = match &loaded_sfo.path {
+      Some(path) if is_pbp_path(path) => save_pbp_dialog(&loaded_sfo.sfo, path),
+      Some(path) if is_iso_path(path) => save_iso_file(path, &loaded_sfo.sfo).map(|_| path.clone()),
+      Some(path) if is_sfx_path(path) => save_sfx_dialog(&loaded_sfo.sfo),
+      _ => save_sfo_dialog(&loaded_sfo.sfo),
+    };
+    match result {
+      Ok(saved_path) => {
+        let game_folder = loaded_sfo
+          .game_folder
+          .clone()
+          .filter(|_| loaded_sfo.path.as_ref() == Some(&saved_path));
+        self.sfo = Some(
+          LoadedSfo::new(loaded_sfo.sfo.clone(), Some(saved_path)).with_game_folder(game_folder),
+        );
+        self.titles_grid.reset();
+        self.inline_edit = None;
       }
+      Err(err_msg) => self.err_msg = Some(err_msg),
     }
   }
 
@@ -191,17 +879,29 @@ impl GuiApp {
         self.err_msg = Some(format!("could not load a sfo file: {err}"));
         None
       },
-      |(sfo, path)| {
-        Some(LoadedSfo {
-          sfo,
-          path,
-          modified: false,
-        })
-      },
+      |(sfo, path)| Some(LoadedSfo::new(sfo, Some(path))),
     );
 
     if new_sfo.is_some() {
       self.sfo = new_sfo;
+      self.titles_grid.reset();
+      self.inline_edit = None;
+    }
+
+    ctx.request_repaint();
+  }
+
+  fn show_open_game_folder_dialog(&mut self, ctx: &egui::Context) {
+    match open_game_folder_dialog().and_then(LoadedSfo::from_game_folder) {
+      Ok(loaded_sfo) => {
+        self.sfo = Some(loaded_sfo);
+        self.assets_panel.reset();
+        self.titles_grid.reset();
+        self.inline_edit = None;
+      }
+      Err(err) => {
+        self.err_msg = Some(format!("could not open a game folder: {err}"));
+      }
     }
 
     ctx.request_repaint();
@@ -228,12 +928,20 @@ impl eframe::App for GuiApp {
                   loaded_sfo.sfo.add(entry.key, entry.field);
                 }
                 entry_update_modal::ModalVariant::Edit => {
-                  if let Err(err_msg) = loaded_sfo.sfo.edit(&entry.key, entry.field) {
+                  let edited = match &entry.renamed_from {
+                    Some(prev_key) => {
+                      loaded_sfo
+                        .sfo
+                        .rename_and_edit(prev_key, entry.key, entry.field)
+                    }
+                    None => loaded_sfo.sfo.edit(&entry.key, entry.field),
+                  };
+                  if let Err(err_msg) = edited {
                     self.err_msg = Some(err_msg);
                   };
                 }
               };
-              loaded_sfo.modified = true;
+              loaded_sfo.refresh();
             }
           }
         },
@@ -247,7 +955,7 @@ impl eframe::App for GuiApp {
       if let Some(confirm) = dialog.show(ctx) {
         if confirm && let Some(loaded_sfo) = &mut self.sfo {
           match loaded_sfo.sfo.delete(&dialog.key) {
-            Ok(_) => loaded_sfo.modified = true,
+            Ok(_) => loaded_sfo.refresh(),
             Err(err) => self.err_msg = Some(err),
           }
         }
@@ -256,14 +964,119 @@ impl eframe::App for GuiApp {
       }
     }
 
+    if let Some(dialog) = self.convert_type_dialog.take() {
+      if let Some(confirm) = dialog.show(ctx) {
+        if confirm && let Some(loaded_sfo) = &mut self.sfo {
+          match loaded_sfo.sfo.convert(&dialog.key) {
+            Ok(_) => loaded_sfo.refresh(),
+            Err(err) => self.err_msg = Some(err),
+          }
+        }
+      } else {
+        self.convert_type_dialog = Some(dialog);
+      }
+    }
+
+    if let Some(mut dialog) = self.new_sfo_dialog.take() {
+      if let Some(confirm) = dialog.show(ctx) {
+        if confirm {
+          self.sfo = Some(LoadedSfo::new(Sfo::empty(dialog.version), None));
+          self.titles_grid.reset();
+          self.inline_edit = None;
+        }
+      } else {
+        self.new_sfo_dialog = Some(dialog);
+      }
+    }
+
+    if let Some(mut dialog) = self.asset_tool_dialog.take() {
+      if let Some(confirm) = dialog.show(ctx) {
+        if confirm && let Some(converted) = &dialog.converted {
+          match save_asset(&dialog.target_dir, dialog.asset, converted) {
+            Ok(_) => {
+              self.assets_panel.reset();
+              if let Some(loaded_sfo) = &mut self.sfo {
+                loaded_sfo.refresh_assets();
+              }
+            }
+            Err(err_msg) => self.err_msg = Some(err_msg),
+          }
+        }
+      } else {
+        self.asset_tool_dialog = Some(dialog);
+      }
+    }
+
+    if let Some(mut dialog) = self.library_dialog.take() {
+      if dialog.show(ctx).is_some() {
+        self.reload_saved_file(&dialog.saved_paths);
+      } else {
+        self.library_dialog = Some(dialog);
+      }
+    }
+
+    if let Some(mut dialog) = self.report_dialog.take() {
+      if let Some(confirm) = dialog.show(ctx) {
+        if confirm {
+          self.export_report(ctx, dialog.format, dialog.scope);
+        }
+      } else {
+        self.report_dialog = Some(dialog);
+      }
+    }
+
+    self.show_report_scan(ctx);
+
+    if let Some(mut dialog) = self.expert_layout_dialog.take() {
+      if let Some(confirm) = dialog.show(ctx) {
+        if confirm && let Some(loaded_sfo) = &mut self.sfo {
+          loaded_sfo.sfo = dialog.sfo;
+          loaded_sfo.refresh();
+        }
+      } else {
+        self.expert_layout_dialog = Some(dialog);
+      }
+    }
+
+    if let Some(dialog) = self.save_summary_dialog.take() {
+      if let Some(confirm) = dialog.show(ctx) {
+        if confirm {
+          self.show_save_sfo_dialog();
+        }
+      } else {
+        self.save_summary_dialog = Some(dialog);
+      }
+    }
+
     if self.sfo.is_some() {
       self.show_header(ctx);
     }
 
     let sfo = self.sfo.take();
     match sfo {
-      Some(sfo) => {
-        self.show_loaded_file(ctx, &sfo);
+      Some(mut sfo) => {
+        match sfo.assets_folder() {
+          Some(assets_folder) => {
+            let errors = self.assets_panel.refresh(ctx, &assets_folder);
+            if !errors.is_empty() {
+              self.err_msg = Some(errors.join("\n"));
+            }
+            self.assets_panel.show(ctx, &assets_folder);
+          }
+          None => self.assets_panel.reset(),
+        }
+        if self.show_xmb_preview {
+          self.xmb_preview.show(
+            ctx,
+            &sfo.sfo,
+            self.assets_panel.texture(Asset::Icon0),
+            self.assets_panel.texture(Asset::Pic1),
+          );
+        }
+        if self.show_inspector {
+          show_inspector_panel(ctx, &sfo.sfo);
+        }
+        self.show_loaded_file(ctx, &mut sfo);
         self.sfo = Some(sfo);
       }
       None => {
@@ -0,0 +1,45 @@
+use eframe::egui::{self};
+use strum::IntoEnumIterator;
+
+use crate::sfo::header::PsfVersion;
+
+pub struct NewSfoDialog {
+  pub version: PsfVersion,
+}
+
+impl NewSfoDialog {
+  pub fn new() -> Self {
+    NewSfoDialog {
+      version: PsfVersion::V1_1,
+    }
+  }
+
+  pub fn show(&mut self, ctx: &eframe::egui::Context) -> Option<bool> {
+    egui::Modal::new(egui::Id::new("new_sfo_dialog"))
+      .show(ctx, |ui| {
+        ui.set_width(250.0);
+        ui.heading("New .sfo file");
+        ui.label("Version of the PSF format");
+        for version in PsfVersion::iter() {
+          ui.radio_value(&mut self.version, version, version.to_string());
+        }
+        ui.separator();
+
+        ui.horizontal(|ui| {
+          let ok_btn = ui.button("Ok");
+          if ok_btn.clicked() {
+            return Some(true);
+          }
+
+          let cancel_btn = ui.button("Cancel");
+          if cancel_btn.clicked() {
+            return Some(false);
+          }
+
+          None
+        })
+        .inner
+      })
+      .inner
+  }
+}
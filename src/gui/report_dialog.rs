@@ -0,0 +1,71 @@
+use eframe::egui::{self};
+use strum::IntoEnumIterator;
+
+use crate::report::ReportFormat;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReportScope {
+  LoadedFile,
+  Library,
+}
+
+/// Chooses the format of the report and whether it covers the loaded file or a library folder.
+pub struct ReportDialog {
+  pub format: ReportFormat,
+  pub scope: ReportScope,
+  file_loaded: bool,
+}
+
+impl ReportDialog {
+  pub fn new(file_loaded: bool) -> Self {
+    ReportDialog {
+      format: ReportFormat::Markdown,
+      scope: match file_loaded {
+        true => ReportScope::LoadedFile,
+        false => ReportScope::Library,
+      },
+      file_loaded,
+    }
+  }
+
+  pub fn show(&mut self, ctx: &eframe::egui::Context) -> Option<bool> {
+    egui::Modal::new(egui::Id::new("report_dialog"))
+      .show(ctx, |ui| {
+        ui.set_width(300.0);
+        ui.heading("Export report");
+
+        ui.label("Format");
+        for format in ReportFormat::iter() {
+          ui.radio_value(&mut self.format, format, format.to_string());
+        }
+
+        ui.label("Files");
+        ui.add_enabled_ui(self.file_loaded, |ui| {
+          ui.radio_value(&mut self.scope, ReportScope::LoadedFile, "Loaded file");
+        });
+        ui.radio_value(
+          &mut self.scope,
+          ReportScope::Library,
+          "All files in a folder",
+        )
+        .on_hover_text("Scan a folder for .sfo, .pbp, .iso and .sfx files");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+          let ok_btn = ui.button("Export");
+          if ok_btn.clicked() {
+            return Some(true);
+          }
+
+          let cancel_btn = ui.button("Cancel");
+          if cancel_btn.clicked() {
+            return Some(false);
+          }
+
+          None
+        })
+        .inner
+      })
+      .inner
+  }
+}
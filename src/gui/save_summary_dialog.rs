@@ -0,0 +1,198 @@
+use std::path::Path;
+
+use eframe::egui::{self};
+
+use crate::{
+  containers::iso::PS3_PARAM_SFO_PATH,
+  file_ops::{is_iso_path, iso_sfo_backup_path},
+  sfo::{
+    Sfo, SfoEntry,
+    diff::{EntryChange, diff},
+  },
+};
+
+pub struct SaveSummaryDialog {
+  entry_changes: Vec<EntryChangeSummary>,
+  header_changes: Vec<HeaderFieldChange>,
+  original_size: u32,
+  size: u32,
+  /// Paths of the ISO image and of the PARAM.SFO backup, set when the sfo is written into an
+  /// image which is modified in place.
+  iso_image: Option<(String, String)>,
+}
+
+struct EntryChangeSummary {
+  key: String,
+  change: EntryChange,
+  before: String,
+  after: String,
+}
+
+struct HeaderFieldChange {
+  name: &'static str,
+  before: u32,
+  after: u32,
+}
+
+impl SaveSummaryDialog {
+  pub fn new(original: &Sfo, current: &Sfo, path: Option<&Path>) -> Self {
+    let entry_changes = diff(original, current)
+      .into_iter()
+      .map(|entry_diff| EntryChangeSummary {
+        key: entry_diff.key.to_string(),
+        change: entry_diff.change,
+        before: entry_diff
+          .original
+          .as_ref()
+          .map_or(String::new(), entry_description),
+        after: current
+          .get_entry(entry_diff.key)
+          .as_ref()
+          .map_or(String::new(), entry_description),
+      })
+      .collect();
+
+    let header_changes = [
+      ("Version", original.header.version, current.header.version),
+      (
+        "Key table start",
+        original.header.key_table_start,
+        current.header.key_table_start,
+      ),
+      (
+        "Data table start",
+        original.header.data_table_start,
+        current.header.data_table_start,
+      ),
+      (
+        "Table entries",
+        original.header.table_entries,
+        current.header.table_entries,
+      ),
+    ]
+    .into_iter()
+    .filter(|(_, before, after)| before != after)
+    .map(|(name, before, after)| HeaderFieldChange {
+      name,
+      before,
+      after,
+    })
+    .collect();
+
+    SaveSummaryDialog {
+      entry_changes,
+      header_changes,
+      original_size: original.file_size(),
+      size: current.file_size(),
+      iso_image: path.filter(|path| is_iso_path(path)).map(|path| {
+        (
+          path.display().to_string(),
+          iso_sfo_backup_path(path).display().to_string(),
+        )
+      }),
+    }
+  }
+
+  pub fn show(&self, ctx: &eframe::egui::Context) -> Option<bool> {
+    egui::Modal::new(egui::Id::new("save_summary_dialog"))
+      .show(ctx, |ui| {
+        ui.set_width(500.0);
+        ui.heading("Save changes?");
+
+        ui.label(format!("Entries ({} changed)", self.entry_changes.len()));
+        egui::ScrollArea::vertical()
+          .max_height(300.0)
+          .show(ui, |ui| {
+            egui::Grid::new("save_summary_entries_grid")
+              .num_columns(4)
+              .spacing([20.0, 4.0])
+              .striped(true)
+              .show(ui, |ui| {
+                for entry_change in &self.entry_changes {
+                  let change_label = match entry_change.change {
+                    EntryChange::Added => "added",
+                    EntryChange::Changed => "changed",
+                    EntryChange::Deleted => "deleted",
+                  };
+                  ui.label(change_label);
+                  ui.label(&entry_change.key);
+                  ui.label(&entry_change.before);
+                  ui.label(&entry_change.after);
+                  ui.end_row();
+                }
+              });
+          });
+        ui.separator();
+
+        ui.label("Header");
+        egui::Grid::new("save_summary_header_grid")
+          .num_columns(2)
+          .spacing([20.0, 4.0])
+          .show(ui, |ui| {
+            if self.header_changes.is_empty() {
+              ui.label("No changes");
+              ui.end_row();
+            }
+
+            for header_change in &self.header_changes {
+              ui.label(header_change.name);
+              ui.label(format!(
+                "{:#X} -> {:#X}",
+                header_change.before, header_change.after
+              ));
+              ui.end_row();
+            }
+
+            ui.label("File size");
+            ui.label(format!(
+              "{} -> {} bytes ({:+})",
+              self.original_size,
+              self.size,
+              self.size as i64 - self.original_size as i64
+            ));
+            ui.end_row();
+          });
+        ui.separator();
+
+        if let Some((iso_image, sfo_backup)) = &self.iso_image {
+          ui.colored_label(
+            ui.visuals().warn_fg_color,
+            format!(
+              "{PS3_PARAM_SFO_PATH} is overwritten in place in {iso_image}. No .bak copy of the image is written - only the previous {PS3_PARAM_SFO_PATH} is kept in {sfo_backup}, unless it is already there."
+            ),
+          );
+          ui.separator();
+        }
+
+        ui.horizontal(|ui| {
+          let ok_label = match self.iso_image {
+            Some(_) => "Overwrite image",
+            None => "Save",
+          };
+          let ok_btn = ui.button(ok_label);
+          if ok_btn.clicked() {
+            return Some(true);
+          }
+
+          let cancel_btn = ui.button("Cancel");
+          if cancel_btn.clicked() {
+            return Some(false);
+          }
+
+          None
+        })
+        .inner
+      })
+      .inner
+  }
+}
+
+fn entry_description(entry: &SfoEntry) -> String {
+  format!(
+    "\"{}\" ({}, {}/{} bytes)",
+    entry.data,
+    entry.index_table_entry.data_format,
+    entry.index_table_entry.data_len,
+    entry.index_table_entry.data_max_len
+  )
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::sfo::{
+  Sfo,
+  keys::Keys,
+  languages::{Language, localized_title},
+};
+
+#[derive(Default)]
+pub struct TitlesGrid {
+  drafts: HashMap<Language, String>,
+}
+
+pub enum TitlesGridAction {
+  Save(Language, String),
+  Clear(Language),
+  ImportCsv,
+  ExportCsv,
+}
+
+impl TitlesGrid {
+  pub fn show(&mut self, ui: &mut egui::Ui, sfo: &Sfo) -> Option<TitlesGridAction> {
+    let mut action: Option<TitlesGridAction> = None;
+    let default_title = sfo
+      .get(&Keys::Title)
+      .map_or(String::new(), |title| title.to_string());
+
+    ui.horizontal(|ui| {
+      if ui.button("Import CSV").clicked() {
+        action = Some(TitlesGridAction::ImportCsv);
+      }
+
+      if ui.button("Export CSV").clicked() {
+        action = Some(TitlesGridAction::ExportCsv);
+      }
+    });
+    ui.label(format!("Default {}: {default_title}", Keys::Title));
+    ui.separator();
+
+    egui::Grid::new("titles_grid")
+      .num_columns(4)
+      .min_col_width(10.0)
+      .max_col_width(ui.available_size().x)
+      .spacing([20.0, 4.0])
+      .striped(true)
+      .show(ui, |ui| {
+        ui.label("");
+        ui.label("LANGUAGE");
+        ui.label("KEY");
+        ui.label("TITLE");
+        ui.end_row();
+
+        for language in Language::iter() {
+          let current = localized_title(sfo, language).map(|title| title.to_string());
+          let mut draft = self
+            .drafts
+            .get(&language)
+            .cloned()
+            .unwrap_or_else(|| current.clone().unwrap_or_default());
+          let changed = draft != current.clone().unwrap_or_default();
+
+          ui.horizontal(|ui| {
+            let save_btn = ui
+              .add_enabled(changed && !draft.is_empty(), egui::Button::new("Save"))
+              .on_disabled_hover_text("Title has not been changed");
+            if save_btn.clicked() {
+              action = Some(TitlesGridAction::Save(language, draft.clone()));
+            }
+
+            let clear_btn = ui
+              .add_enabled(current.is_some(), egui::Button::new("Clear"))
+              .on_disabled_hover_text("No localized title to clear, default title is used");
+            if clear_btn.clicked() {
+              action = Some(TitlesGridAction::Clear(language));
+            }
+          });
+
+          ui.label(language.to_string());
+          ui.label(language.title_key().to_string());
+          let title_input =
+            ui.add(egui::TextEdit::singleline(&mut draft).hint_text(&default_title));
+          if title_input.changed() {
+            self.drafts.insert(language, draft);
+          }
+          ui.end_row();
+        }
+      });
+
+    if let Some(TitlesGridAction::Save(language, _) | TitlesGridAction::Clear(language)) = &action {
+      self.drafts.remove(language);
+    }
+
+    action
+  }
+
+  pub fn reset(&mut self) {
+    self.drafts.clear();
+  }
+}
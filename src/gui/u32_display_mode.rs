@@ -0,0 +1,130 @@
+use egui_typed_input::ValText;
+
+use crate::sfo::keys::Keys;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, strum::Display, strum::EnumIter)]
+pub enum U32DisplayMode {
+  #[strum(serialize = "dec")]
+  Decimal,
+  #[strum(serialize = "hex")]
+  Hex,
+  #[strum(serialize = "bin")]
+  Binary,
+  #[strum(serialize = "flags")]
+  Flags,
+}
+
+impl U32DisplayMode {
+  pub fn for_key(key: &Keys) -> Self {
+    match key.is_bitmask() {
+      true => U32DisplayMode::Hex,
+      false => U32DisplayMode::Decimal,
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      U32DisplayMode::Decimal => U32DisplayMode::Hex,
+      U32DisplayMode::Hex => U32DisplayMode::Binary,
+      U32DisplayMode::Binary => U32DisplayMode::Flags,
+      U32DisplayMode::Flags => U32DisplayMode::Decimal,
+    }
+  }
+
+  pub fn format(&self, val: u32) -> String {
+    match self {
+      U32DisplayMode::Decimal => format!("{val}"),
+      U32DisplayMode::Hex => format!("{val:#010X}"),
+      U32DisplayMode::Binary => format!("{val:#b}"),
+      U32DisplayMode::Flags => {
+        let flags: Vec<String> = (0..u32::BITS)
+          .map(|bit| 1u32 << bit)
+          .filter(|flag| val & flag != 0)
+          .map(|flag| format!("{flag:#X}"))
+          .collect();
+        match flags.is_empty() {
+          true => String::from("0x0"),
+          false => flags.join(" | "),
+        }
+      }
+    }
+  }
+}
+
+/// Parses decimal, `0x`-prefixed hexadecimal and `0b`-prefixed binary numbers. Numbers separated
+/// with `|` are OR-ed together, so values displayed as flags can be parsed back.
+pub fn parse_u32(text: &str) -> Result<u32, String> {
+  text
+    .split('|')
+    .try_fold(0, |acc, part| Ok(acc | parse_single_u32(part.trim())?))
+}
+
+fn parse_single_u32(text: &str) -> Result<u32, String> {
+  let digits = text.replace('_', "");
+  let (digits, radix) = if let Some(hex) = digits
+    .strip_prefix("0x")
+    .or_else(|| digits.strip_prefix("0X"))
+  {
+    (hex, 16)
+  } else if let Some(bin) = digits
+    .strip_prefix("0b")
+    .or_else(|| digits.strip_prefix("0B"))
+  {
+    (bin, 2)
+  } else {
+    (digits.as_str(), 10)
+  };
+
+  u32::from_str_radix(digits, radix)
+    .map_err(|err| format!("\"{text}\" is not a valid unsigned 32-bit number: {err}"))
+}
+
+pub fn u32_input() -> ValText<u32, String> {
+  ValText::new(parse_u32, |_, input, _| {
+    input
+      .chars()
+      .all(|c| c.is_ascii_hexdigit() || matches!(c, 'x' | 'X' | '_' | '|' | ' '))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use strum::IntoEnumIterator;
+
+  use super::*;
+
+  #[test]
+  fn parses_every_prefix() {
+    assert_eq!(parse_single_u32("42"), Ok(42));
+    assert_eq!(parse_single_u32("0x2A"), Ok(42));
+    assert_eq!(parse_single_u32("0X2a"), Ok(42));
+    assert_eq!(parse_single_u32("0b101010"), Ok(42));
+    assert_eq!(parse_single_u32("0B101010"), Ok(42));
+    assert_eq!(parse_single_u32("1_000_000"), Ok(1_000_000));
+    assert_eq!(parse_single_u32("0xFFFF_FFFF"), Ok(u32::MAX));
+  }
+
+  #[test]
+  fn parses_joined_flags() {
+    assert_eq!(parse_u32("0x1 | 0x20 | 0b100"), Ok(0x25));
+    assert_eq!(parse_u32("0x1|0x1"), Ok(0x1));
+  }
+
+  #[test]
+  fn rejects_overflow_and_empty_input() {
+    assert!(parse_u32("4294967296").is_err());
+    assert!(parse_u32("0x1_0000_0000").is_err());
+    assert!(parse_u32("").is_err());
+    assert!(parse_u32("0x").is_err());
+    assert!(parse_u32("0x1 |").is_err());
+  }
+
+  #[test]
+  fn formatted_values_parse_back() {
+    for mode in U32DisplayMode::iter() {
+      for val in [0, 1, 0x20, 0x8000_0001, u32::MAX] {
+        assert_eq!(parse_u32(&mode.format(val)), Ok(val), "{mode} {val}");
+      }
+    }
+  }
+}
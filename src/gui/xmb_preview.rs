@@ -0,0 +1,138 @@
+use eframe::egui::{self, Align2, Color32, FontId, Pos2, Rect, Vec2};
+use strum::IntoEnumIterator;
+
+use crate::sfo::{
+  Sfo,
+  keys::Keys,
+  languages::{Language, localized_title},
+  mapping::DataField,
+};
+
+const TILE_SIZE: Vec2 = Vec2::new(960.0, 270.0);
+const ICON_SIZE: Vec2 = Vec2::new(320.0, 176.0);
+const MARGIN: f32 = 24.0;
+const XMB_BACKGROUND: Color32 = Color32::from_rgb(20, 40, 90);
+const SAVE_DATA_CATEGORY: &str = "SD";
+
+/// Approximation of the XMB entry of the loaded file.
+#[derive(Default)]
+pub struct XmbPreview {
+  language: Option<Language>,
+}
+
+impl XmbPreview {
+  pub fn show(
+    &mut self,
+    ctx: &egui::Context,
+    sfo: &Sfo,
+    icon: Option<&egui::TextureHandle>,
+    background: Option<&egui::TextureHandle>,
+  ) {
+    egui::TopBottomPanel::bottom("xmb_preview_panel")
+      .resizable(true)
+      .show(ctx, |ui| {
+        ui.horizontal(|ui| {
+          ui.heading("XMB preview");
+          self.language_combo_box(ui);
+        });
+
+        egui::ScrollArea::both().show(ui, |ui| {
+          self.draw_tile(ui, sfo, icon, background);
+        });
+      });
+  }
+
+  fn language_combo_box(&mut self, ui: &mut egui::Ui) {
+    let selected_text = self
+      .language
+      .map_or(String::from("Default"), |language| language.to_string());
+
+    egui::ComboBox::from_id_salt("xmb_preview_language")
+      .selected_text(format!("Language: {selected_text}"))
+      .show_ui(ui, |ui| {
+        ui.selectable_value(&mut self.language, None, "Default");
+        for language in Language::iter() {
+          ui.selectable_value(&mut self.language, Some(language), language.to_string());
+        }
+      });
+  }
+
+  fn draw_tile(
+    &self,
+    ui: &mut egui::Ui,
+    sfo: &Sfo,
+    icon: Option<&egui::TextureHandle>,
+    background: Option<&egui::TextureHandle>,
+  ) {
+    let (rect, _) = ui.allocate_exact_size(TILE_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+    match background {
+      Some(background) => painter.image(background.id(), rect, uv, Color32::WHITE),
+      None => painter.rect_filled(rect, 0.0, XMB_BACKGROUND),
+    };
+
+    let icon_rect = Rect::from_min_size(
+      rect.left_top() + Vec2::new(MARGIN, (rect.height() - ICON_SIZE.y) / 2.0),
+      ICON_SIZE,
+    );
+    match icon {
+      Some(icon) => {
+        painter.image(icon.id(), icon_rect, uv, Color32::WHITE);
+      }
+      None => {
+        painter.rect_stroke(
+          icon_rect,
+          0.0,
+          (1.0, Color32::GRAY),
+          egui::StrokeKind::Inside,
+        );
+        painter.text(
+          icon_rect.center(),
+          Align2::CENTER_CENTER,
+          "No ICON0",
+          FontId::proportional(16.0),
+          Color32::GRAY,
+        );
+      }
+    }
+
+    let text_left = icon_rect.right() + MARGIN;
+    let wrap_width = rect.right() - text_left - MARGIN;
+    let mut text_top = icon_rect.top();
+    let mut lines: Vec<(String, f32, Color32)> = vec![(self.title(sfo), 24.0, Color32::WHITE)];
+    if let Some(app_ver) = text_value(sfo, &Keys::AppVer) {
+      lines.push((format!("Ver. {app_ver}"), 14.0, Color32::LIGHT_GRAY));
+    }
+    if text_value(sfo, &Keys::Category).as_deref() == Some(SAVE_DATA_CATEGORY) {
+      if let Some(sub_title) = text_value(sfo, &Keys::SubTitle) {
+        lines.push((sub_title, 16.0, Color32::LIGHT_GRAY));
+      }
+      if let Some(detail) = text_value(sfo, &Keys::Detail) {
+        lines.push((detail, 13.0, Color32::LIGHT_GRAY));
+      }
+    }
+
+    for (text, size, color) in lines {
+      let galley = painter.layout(text, FontId::proportional(size), color, wrap_width);
+      let height = galley.size().y;
+      painter.galley(Pos2::new(text_left, text_top), galley, color);
+      text_top += height + size / 2.0;
+    }
+  }
+
+  fn title(&self, sfo: &Sfo) -> String {
+    self
+      .language
+      .and_then(|language| localized_title(sfo, language))
+      .or_else(|| sfo.get(&Keys::Title))
+      .map_or(String::from("(no TITLE)"), |title| title.to_string())
+  }
+}
+
+fn text_value(sfo: &Sfo, key: &Keys) -> Option<String> {
+  match sfo.get(key)? {
+    DataField::Utf8String(text) if !text.is_empty() => Some(text.clone()),
+    _ => None,
+  }
+}
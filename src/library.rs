@@ -0,0 +1,450 @@
+use std::{
+  collections::HashMap,
+  fs,
+  io::{Read, Write},
+  path::{Path, PathBuf},
+};
+
+use crate::{
+  file_ops::{is_iso_path, is_pbp_path, is_sfx_path, load_sfo_file, save_sfo_in_place},
+  sfo::{Sfo, format::Format, keys::Keys, mapping::DataField},
+};
+
+pub const PATH_COLUMN: &str = "path";
+const SFO_FILE_NAME: &str = "PARAM.SFO";
+
+/// A file holding PARAM.SFO found while scanning a library folder.
+pub struct LibraryEntry {
+  pub path: PathBuf,
+  pub sfo: Sfo,
+}
+
+/// Recursively finds PARAM.SFO files together with .pbp, .iso and .sfx files holding one under
+/// the root folder. Files which could not be loaded are reported next to the found entries.
+pub fn scan_library<T>(root: T) -> (Vec<LibraryEntry>, Vec<String>)
+where
+  T: AsRef<Path>,
+{
+  scan_library_with_progress(root, |_| {})
+}
+
+/// Same as `scan_library`, calling `on_file` with the path of every library file before it is
+/// loaded.
+pub fn scan_library_with_progress<T, F>(root: T, mut on_file: F) -> (Vec<LibraryEntry>, Vec<String>)
+where
+  T: AsRef<Path>,
+  F: FnMut(&Path),
+{
+  let mut entries = Vec::new();
+  let mut issues = Vec::new();
+  let mut dirs = vec![root.as_ref().to_path_buf()];
+
+  while let Some(dir) = dirs.pop() {
+    let dir_entries = match fs::read_dir(&dir) {
+      Ok(dir_entries) => dir_entries,
+      Err(err) => {
+        issues.push(format!("could not read directory {}: {err}", dir.display()));
+        continue;
+      }
+    };
+
+    for dir_entry in dir_entries.filter_map(|dir_entry| dir_entry.ok()) {
+      let path = dir_entry.path();
+      let Ok(file_type) = dir_entry.file_type() else {
+        continue;
+      };
+
+      if file_type.is_dir() {
+        dirs.push(path);
+        continue;
+      }
+
+      if !is_library_file(&path) {
+        continue;
+      }
+
+      on_file(&path);
+      match load_sfo_file(&path) {
+        Ok(sfo) => entries.push(LibraryEntry { path, sfo }),
+        Err(err) => issues.push(format!("{}: {err}", path.display())),
+      }
+    }
+  }
+
+  entries.sort_by(|a, b| a.path.cmp(&b.path));
+  (entries, issues)
+}
+
+fn is_library_file(path: &Path) -> bool {
+  let is_sfo = path.file_name().is_some_and(|file_name| {
+    file_name
+      .to_string_lossy()
+      .eq_ignore_ascii_case(SFO_FILE_NAME)
+  });
+
+  is_sfo || is_pbp_path(path) || is_iso_path(path) || is_sfx_path(path)
+}
+
+/// Writes a row with the path and values of the keys for every entry. Keys missing in a file
+/// are left empty.
+pub fn export_library_csv<T>(
+  entries: &[LibraryEntry],
+  keys: &[Keys],
+  writer: &mut T,
+) -> Result<(), String>
+where
+  T: Write,
+{
+  let mut csv_writer = csv::Writer::from_writer(writer);
+  let header = std::iter::once(String::from(PATH_COLUMN)).chain(keys.iter().map(Keys::to_string));
+  csv_writer
+    .write_record(header)
+    .map_err(|err| format!("could not write csv header: {err}"))?;
+
+  for entry in entries {
+    let values = keys.iter().map(|key| {
+      entry
+        .sfo
+        .get(key)
+        .map_or(String::new(), DataField::to_string)
+    });
+    csv_writer
+      .write_record(std::iter::once(entry.path.to_string_lossy().to_string()).chain(values))
+      .map_err(|err| {
+        format!(
+          "could not write csv row for {}: {err}",
+          entry.path.display()
+        )
+      })?;
+  }
+
+  csv_writer
+    .flush()
+    .map_err(|err| format!("could not flush csv writer: {err}"))
+}
+
+pub struct PlannedEdit {
+  pub key: Keys,
+  pub old: DataField,
+  pub new: DataField,
+}
+
+/// Edits of a single file, together with the file already edited in memory.
+pub struct PlannedFile {
+  pub path: PathBuf,
+  pub edits: Vec<PlannedEdit>,
+  sfo: Sfo,
+}
+
+/// Changes that an edited library csv would apply. Rows and values which could not be applied
+/// are listed as issues and skipped.
+#[derive(Default)]
+pub struct LibraryImportPlan {
+  pub files: Vec<PlannedFile>,
+  pub issues: Vec<String>,
+}
+
+impl LibraryImportPlan {
+  pub fn edits_count(&self) -> usize {
+    self.files.iter().map(|file| file.edits.len()).sum()
+  }
+
+  /// Writes the planned files back to their paths, backing up each file unless its backup already
+  /// exists. Returns the saved paths and the errors of files which could not be saved.
+  pub fn apply(&self) -> (Vec<PathBuf>, Vec<String>) {
+    let mut saved = Vec::new();
+    let mut errors = Vec::new();
+    for file in &self.files {
+      match save_sfo_in_place(&file.path, &file.sfo) {
+        Ok(_) => saved.push(file.path.clone()),
+        Err(err) => errors.push(format!("{}: {err}", file.path.display())),
+      }
+    }
+
+    (saved, errors)
+  }
+}
+
+/// Reads library csv exported with `export_library_csv` and compares its values with the files
+/// it references, without writing anything. Only values of existing entries are changed, within
+/// their reserved size. Rows repeating a file of an earlier row are reported and skipped, so that
+/// no file is written twice.
+pub fn plan_library_csv_import<T>(reader: &mut T) -> Result<LibraryImportPlan, String>
+where
+  T: Read,
+{
+  let mut csv_reader = csv::Reader::from_reader(reader);
+  let headers = csv_reader
+    .headers()
+    .map_err(|err| format!("could not read csv header: {err}"))?
+    .clone();
+  if headers.get(0) != Some(PATH_COLUMN) {
+    return Err(format!(
+      "first column of csv header has to be \"{PATH_COLUMN}\""
+    ));
+  }
+  let keys: Vec<Keys> = headers
+    .iter()
+    .skip(1)
+    .map(|name| {
+      name
+        .parse()
+        .unwrap_or_else(|_| Keys::Unknown(name.to_owned()))
+    })
+    .collect();
+
+  let mut plan = LibraryImportPlan::default();
+  let mut rows_by_file: HashMap<PathBuf, usize> = HashMap::new();
+  for (idx, record) in csv_reader.records().enumerate() {
+    let row = idx + 1;
+    let record = record.map_err(|err| format!("could not read csv row {row}: {err}"))?;
+    let Some(path) = record.get(0).filter(|path| !path.is_empty()) else {
+      plan.issues.push(format!("csv row {row} is missing path"));
+      continue;
+    };
+
+    let path = PathBuf::from(path);
+    let file = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+    if let Some(first_row) = rows_by_file.get(&file) {
+      plan.issues.push(format!(
+        "csv row {row}: {} is already listed in csv row {first_row}",
+        path.display()
+      ));
+      continue;
+    }
+    rows_by_file.insert(file, row);
+
+    let mut sfo = match load_sfo_file(&path) {
+      Ok(sfo) => sfo,
+      Err(err) => {
+        plan
+          .issues
+          .push(format!("csv row {row}: {}: {err}", path.display()));
+        continue;
+      }
+    };
+
+    let mut edits = Vec::new();
+    for (key, value) in keys.iter().zip(record.iter().skip(1)) {
+      match plan_edit(&mut sfo, key, value) {
+        Ok(Some(edit)) => edits.push(edit),
+        Ok(None) => {}
+        Err(err) => plan
+          .issues
+          .push(format!("csv row {row}: {}: {err}", path.display())),
+      }
+    }
+
+    if !edits.is_empty() {
+      plan.files.push(PlannedFile { path, edits, sfo });
+    }
+  }
+
+  Ok(plan)
+}
+
+/// Checks the value against the key limits and the reserved size of the entry the same way as
+/// inline editing does, as `Sfo::edit` would grow the reserved size instead.
+fn plan_edit(sfo: &mut Sfo, key: &Keys, value: &str) -> Result<Option<PlannedEdit>, String> {
+  let Some(entry) = sfo.get_entry(key) else {
+    return match value.is_empty() {
+      true => Ok(None),
+      false => Err(format!(
+        "key {key} is not present, adding keys is not supported"
+      )),
+    };
+  };
+
+  let old = entry.data.clone();
+  let new = match old {
+    DataField::U32(_) => value
+      .trim()
+      .parse::<u32>()
+      .map(DataField::U32)
+      .map_err(|err| {
+        format!("value \"{value}\" of key {key} is not an unsigned 32-bit integer: {err}")
+      })?,
+    DataField::Utf8String(_) => {
+      let null_terminated = entry.index_table_entry.data_format != Format::Utf8Special;
+      let text_len = value.len() as u32 + u32::from(null_terminated);
+      if let Some(max_len) = key.max_len()
+        && text_len > max_len
+      {
+        return Err(format!(
+          "value of key {key} takes {text_len} bytes which exceeds maximum of {max_len} bytes"
+        ));
+      }
+
+      let reserved_len = entry.index_table_entry.data_max_len;
+      if text_len > reserved_len {
+        return Err(format!(
+          "value of key {key} takes {text_len} bytes which exceeds reserved size of {reserved_len} bytes"
+        ));
+      }
+
+      DataField::Utf8String(value.to_owned())
+    }
+  };
+  if new == old {
+    return Ok(None);
+  }
+
+  sfo.edit(key, new.clone())?;
+  Ok(Some(PlannedEdit {
+    key: key.clone(),
+    old,
+    new,
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use super::*;
+  use crate::{
+    file_ops::{backup_path, save_sfo_file},
+    sfo::header::PsfVersion,
+  };
+
+  /// Empty folder in the system temporary directory, unique for the test.
+  fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sfo-tool-library-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  fn write_sfo(dir: &Path, title: &str) -> PathBuf {
+    let mut sfo = Sfo::empty(PsfVersion::V1_1);
+    sfo
+      .add_with_layout(
+        Keys::Title,
+        DataField::Utf8String(String::from(title)),
+        Format::Utf8,
+        128,
+      )
+      .unwrap();
+    sfo.add(Keys::Attribute, DataField::U32(0));
+    let path = dir.join(SFO_FILE_NAME);
+    save_sfo_file(&path, &sfo).unwrap();
+    path
+  }
+
+  fn plan(csv: &str) -> LibraryImportPlan {
+    plan_library_csv_import(&mut Cursor::new(csv)).unwrap()
+  }
+
+  #[test]
+  fn plans_only_changed_values() {
+    let dir = test_dir("changed");
+    let path = write_sfo(&dir, "Old");
+
+    let plan = plan(&format!("path,TITLE,ATTRIBUTE\n{},New,0\n", path.display()));
+
+    assert!(plan.issues.is_empty(), "{:?}", plan.issues);
+    assert_eq!(plan.edits_count(), 1);
+    assert!(plan.files[0].edits[0].key == Keys::Title);
+    assert_eq!(
+      load_sfo_file(&path)
+        .unwrap()
+        .get(&Keys::Title)
+        .unwrap()
+        .to_string(),
+      "Old"
+    );
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn reports_missing_keys_invalid_values_and_missing_files() {
+    let dir = test_dir("issues");
+    let path = write_sfo(&dir, "Old");
+
+    let plan = plan(&format!(
+      "path,ATTRIBUTE,TITLE_ID\n{},x,BLUS12345\n{},0,\n,0,\n",
+      path.display(),
+      dir.join("missing.sfo").display()
+    ));
+
+    assert!(plan.files.is_empty());
+    assert_eq!(plan.issues.len(), 4, "{:?}", plan.issues);
+    assert!(plan.issues[0].starts_with("csv row 1:"));
+    assert!(plan.issues[2].starts_with("csv row 2:"));
+    assert_eq!(plan.issues[3], "csv row 3 is missing path");
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn reports_values_over_key_limit_or_reserved_size() {
+    let dir = test_dir("limits");
+    let path = write_sfo(&dir, "Old");
+    let mut sfo = load_sfo_file(&path).unwrap();
+    sfo.resize(&Keys::Title, 8).unwrap();
+    save_sfo_file(&path, &sfo).unwrap();
+
+    let too_long = plan(&format!(
+      "path,TITLE\n{},{}\n",
+      path.display(),
+      "a".repeat(128)
+    ));
+    let over_reserved = plan(&format!("path,TITLE\n{},Old Game\n", path.display()));
+
+    assert!(too_long.files.is_empty() && over_reserved.files.is_empty());
+    assert_eq!(
+      too_long.issues,
+      [format!(
+        "csv row 1: {}: value of key TITLE takes 129 bytes which exceeds maximum of 128 bytes",
+        path.display()
+      )]
+    );
+    assert_eq!(
+      over_reserved.issues,
+      [format!(
+        "csv row 1: {}: value of key TITLE takes 9 bytes which exceeds reserved size of 8 bytes",
+        path.display()
+      )]
+    );
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn skips_rows_repeating_a_file() {
+    let dir = test_dir("duplicates");
+    let path = write_sfo(&dir, "Old");
+    let other_path = dir.join(".").join(SFO_FILE_NAME);
+
+    let plan = plan(&format!(
+      "path,TITLE\n{},First\n{},Second\n",
+      path.display(),
+      other_path.display()
+    ));
+
+    assert_eq!(plan.files.len(), 1);
+    assert_eq!(plan.issues.len(), 1);
+    assert!(
+      plan.issues[0].contains("already listed in csv row 1"),
+      "{:?}",
+      plan.issues
+    );
+
+    let (saved, errors) = plan.apply();
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(saved, vec![path.clone()]);
+    assert_eq!(
+      load_sfo_file(&path)
+        .unwrap()
+        .get(&Keys::Title)
+        .unwrap()
+        .to_string(),
+      "First"
+    );
+    assert!(backup_path(&path).exists());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn rejects_csv_without_path_column() {
+    assert!(plan_library_csv_import(&mut Cursor::new("TITLE\nGame\n")).is_err());
+  }
+}
@@ -2,9 +2,16 @@ use std::{error::Error, path::PathBuf};
 
 use clap::Parser;
 
-use crate::gui::GuiApp;
+use crate::{cli::Command, gui::GuiApp};
 
+mod assets;
+mod cli;
+mod containers;
+mod file_ops;
+mod game_folder;
 mod gui;
+mod library;
+mod report;
 mod sfo;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -12,12 +19,22 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[derive(Parser, Debug)]
 #[command(version = VERSION, about = "analysis of PS3 .sfo files", long_about = None)]
 struct Args {
-  #[arg(long, required = false, help = "Path to a .sfo file")]
+  #[arg(
+    long,
+    required = false,
+    help = "Path to a .sfo, .pbp or .iso file, or a game folder"
+  )]
   input_file: Option<PathBuf>,
+  #[command(subcommand)]
+  command: Option<Command>,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
   let args = Args::parse();
+  if let Some(command) = args.command {
+    return cli::run(command).map_err(Into::into);
+  }
+
   let native_options = eframe::NativeOptions::default();
   eframe::run_native(
     "Read .sfo",
@@ -0,0 +1,310 @@
+use std::path::Path;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use quick_xml::escape::escape;
+use strum::IntoEnumIterator;
+
+use crate::sfo::{Sfo, keys::Keys, languages::Language, mapping::DataField};
+
+const REPORT_TITLE: &str = "PARAM.SFO report";
+const ENTRY_COLUMNS: [&str; 8] = [
+  "Key",
+  "Value",
+  "Decoded",
+  "Format",
+  "Length",
+  "Max length",
+  "Key offset",
+  "Data offset",
+];
+const HTML_STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #999; padding: 0.25em 0.5em; text-align: left; vertical-align: top; }
+th { background: #eee; }
+td { white-space: pre-wrap; }
+img { display: block; margin-bottom: 1em; }";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumIter, clap::ValueEnum)]
+pub enum ReportFormat {
+  Markdown,
+  #[strum(serialize = "HTML")]
+  Html,
+}
+
+impl ReportFormat {
+  pub fn extension(&self) -> &'static str {
+    match self {
+      ReportFormat::Markdown => "md",
+      ReportFormat::Html => "html",
+    }
+  }
+}
+
+/// A file included in the report, with the PNG bytes of its ICON0 when available.
+pub struct ReportFile<'a> {
+  pub path: Option<&'a Path>,
+  pub sfo: &'a Sfo,
+  pub icon: Option<Vec<u8>>,
+}
+
+/// Contents of a single file, shared by both formats of the report.
+struct FileSummary {
+  heading: String,
+  path: String,
+  icon: Option<String>,
+  header_rows: Vec<(&'static str, String)>,
+  entry_rows: Vec<[String; 8]>,
+}
+
+impl FileSummary {
+  fn new(file: &ReportFile) -> Self {
+    let sfo = file.sfo;
+    let heading = match (sfo.get(&Keys::Title), sfo.get(&Keys::TitleId)) {
+      (Some(title), Some(title_id)) => format!("{title} ({title_id})"),
+      (Some(title), None) => title.to_string(),
+      (None, Some(title_id)) => title_id.to_string(),
+      (None, None) => String::from("Untitled"),
+    };
+    let path = file
+      .path
+      .map_or(String::from("New file (not saved yet)"), |path| {
+        path.to_string_lossy().to_string()
+      });
+
+    let header = &sfo.header;
+    let header_rows = vec![
+      (
+        "Version",
+        format!("{} [{:#010X}]", header.version_name(), header.version),
+      ),
+      (
+        "Key table start",
+        format!("{:#010X}", header.key_table_start),
+      ),
+      (
+        "Data table start",
+        format!("{:#010X}", header.data_table_start),
+      ),
+      ("Entries", header.table_entries.to_string()),
+      ("Key table padding", format!("{} bytes", sfo.padding)),
+      ("File size", format!("{} bytes", sfo.file_size())),
+    ];
+
+    let entry_rows = sfo
+      .iter()
+      .map(|(key, entry)| {
+        let index_table_entry = entry.index_table_entry;
+        [
+          key.to_string(),
+          entry.data.to_string(),
+          decoded_value(key, entry.data),
+          index_table_entry.data_format.to_string(),
+          format!("{} bytes", index_table_entry.data_len),
+          format!("{} bytes", index_table_entry.data_max_len),
+          format!("{:#06X}", index_table_entry.key_offset),
+          format!("{:#010X}", index_table_entry.data_offset),
+        ]
+      })
+      .collect();
+
+    FileSummary {
+      heading,
+      path,
+      icon: file.icon.as_ref().map(|icon| STANDARD.encode(icon)),
+      header_rows,
+      entry_rows,
+    }
+  }
+}
+
+/// Meaning of the value when it is known - description of a well-known value, flags of a bitmask
+/// and the language of a localized title.
+fn decoded_value(key: &Keys, data: &DataField) -> String {
+  let mut decoded: Vec<String> = key
+    .suggested_values()
+    .into_iter()
+    .filter(|(value, _)| value == data)
+    .map(|(_, description)| String::from(description))
+    .collect();
+
+  if let DataField::U32(val) = data {
+    decoded.push(format!("{val:#010X}"));
+    if key.is_bitmask() && *val != 0 {
+      let flags: Vec<String> = (0..u32::BITS)
+        .map(|bit| 1u32 << bit)
+        .filter(|flag| val & flag != 0)
+        .map(|flag| format!("{flag:#X}"))
+        .collect();
+      decoded.push(format!("flags {}", flags.join(" | ")));
+    }
+  }
+
+  if let Some(language) = Language::iter().find(|language| language.title_key() == *key) {
+    decoded.push(format!("title in {language}"));
+  }
+
+  decoded.join(", ")
+}
+
+/// Renders header, index table and values of the files. HTML report is self-contained, with
+/// icons embedded as data URIs. Markdown report leaves icons out, as viewers commonly block data
+/// URIs in images.
+pub fn render_report(files: &[ReportFile], format: ReportFormat) -> String {
+  let summaries: Vec<FileSummary> = files.iter().map(FileSummary::new).collect();
+  match format {
+    ReportFormat::Markdown => render_markdown(&summaries),
+    ReportFormat::Html => render_html(&summaries),
+  }
+}
+
+fn render_markdown(summaries: &[FileSummary]) -> String {
+  let mut report = format!("# {REPORT_TITLE}\n");
+  for summary in summaries {
+    report.push_str(&format!(
+      "\n## {}\n\nPath: {}\n\n",
+      markdown_cell(&summary.heading),
+      markdown_code_span(&summary.path)
+    ));
+
+    report.push_str("| Header | |\n| --- | --- |\n");
+    for (name, value) in &summary.header_rows {
+      report.push_str(&format!("| {name} | {} |\n", markdown_cell(value)));
+    }
+
+    report.push_str(&format!(
+      "\n| {} |\n|{}\n",
+      ENTRY_COLUMNS.join(" | "),
+      " --- |".repeat(ENTRY_COLUMNS.len())
+    ));
+    for row in &summary.entry_rows {
+      let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+      report.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+  }
+
+  report
+}
+
+/// Escapes text placed in a table cell or a heading. HTML is allowed in Markdown, so `<`, `>`
+/// and `&` are written as entities.
+fn markdown_cell(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('|', "\\|")
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('\n', "<br>")
+}
+
+/// Wraps the text in a code span delimited by more backticks than the longest run of backticks in
+/// the text, padded with spaces when the text starts or ends with a backtick.
+fn markdown_code_span(text: &str) -> String {
+  let longest_run = text
+    .split(|ch| ch != '`')
+    .map(str::len)
+    .max()
+    .unwrap_or_default();
+  let fence = "`".repeat(longest_run + 1);
+  let padding = match text.starts_with('`') || text.ends_with('`') {
+    true => " ",
+    false => "",
+  };
+  format!("{fence}{padding}{text}{padding}{fence}")
+}
+
+fn render_html(summaries: &[FileSummary]) -> String {
+  let mut report = format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{REPORT_TITLE}</title>\n<style>\n{HTML_STYLE}\n</style>\n</head>\n<body>\n<h1>{REPORT_TITLE}</h1>\n"
+  );
+  for summary in summaries {
+    report.push_str(&format!(
+      "<h2>{}</h2>\n<p>Path: <code>{}</code></p>\n",
+      escape(summary.heading.as_str()),
+      escape(summary.path.as_str())
+    ));
+    if let Some(icon) = &summary.icon {
+      report.push_str(&format!(
+        "<img alt=\"ICON0\" src=\"data:image/png;base64,{icon}\">\n"
+      ));
+    }
+
+    report.push_str("<table>\n<tr><th colspan=\"2\">Header</th></tr>\n");
+    for (name, value) in &summary.header_rows {
+      report.push_str(&format!(
+        "<tr><th>{name}</th><td>{}</td></tr>\n",
+        escape(value.as_str())
+      ));
+    }
+    report.push_str("</table>\n<table>\n<tr>");
+    for column in ENTRY_COLUMNS {
+      report.push_str(&format!("<th>{column}</th>"));
+    }
+    report.push_str("</tr>\n");
+    for row in &summary.entry_rows {
+      report.push_str("<tr>");
+      for cell in row {
+        report.push_str(&format!("<td>{}</td>", escape(cell.as_str())));
+      }
+      report.push_str("</tr>\n");
+    }
+    report.push_str("</table>\n");
+  }
+
+  report.push_str("</body>\n</html>\n");
+  report
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sfo::test_fixtures::sfo_with_title;
+
+  fn report(title: &str, path: &str, format: ReportFormat) -> String {
+    let sfo = sfo_with_title(title);
+    let file = ReportFile {
+      path: Some(Path::new(path)),
+      sfo: &sfo,
+      icon: Some(vec![0x89, b'P', b'N', b'G']),
+    };
+    render_report(&[file], format)
+  }
+
+  #[test]
+  fn markdown_escapes_table_cells() {
+    let report = report("A|B <b>&</b>\\", "game.sfo", ReportFormat::Markdown);
+
+    assert!(
+      report.contains("| A\\|B &lt;b&gt;&amp;&lt;/b&gt;\\\\ |"),
+      "{report}"
+    );
+    assert!(!report.contains("<b>"), "{report}");
+  }
+
+  #[test]
+  fn markdown_path_code_span_fits_backticks() {
+    assert_eq!(markdown_code_span("a/b.sfo"), "`a/b.sfo`");
+    assert_eq!(markdown_code_span("a``b"), "```a``b```");
+    assert_eq!(markdown_code_span("`a`"), "`` `a` ``");
+
+    let report = report("Game", "games/`x`/PARAM.SFO", ReportFormat::Markdown);
+    assert!(report.contains("Path: ``games/`x`/PARAM.SFO``"), "{report}");
+  }
+
+  #[test]
+  fn markdown_leaves_icons_out() {
+    let report = report("Game", "game.sfo", ReportFormat::Markdown);
+
+    assert!(!report.contains("data:image"), "{report}");
+  }
+
+  #[test]
+  fn html_escapes_values_and_embeds_icons() {
+    let report = report("<script>", "a&b.sfo", ReportFormat::Html);
+
+    assert!(report.contains("&lt;script&gt;"), "{report}");
+    assert!(!report.contains("<script>"), "{report}");
+    assert!(report.contains("<code>a&amp;b.sfo</code>"), "{report}");
+    assert!(report.contains("src=\"data:image/png;base64,"), "{report}");
+  }
+}
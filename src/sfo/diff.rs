@@ -0,0 +1,246 @@
+use crate::sfo::{Sfo, SfoEntry, keys::Keys};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryChange {
+  Added,
+  Changed,
+  Deleted,
+}
+
+pub struct EntryDiff<'a> {
+  pub key: &'a Keys,
+  pub change: EntryChange,
+  pub original: Option<SfoEntry<'a>>,
+}
+
+/// Lists entries which differ between the original and the current sfo, in the order of keys.
+pub fn diff<'a>(original: &'a Sfo, current: &'a Sfo) -> Vec<EntryDiff<'a>> {
+  let mut diffs: Vec<EntryDiff> = current
+    .iter()
+    .filter_map(|(key, current_entry)| match original.get_entry(key) {
+      None => Some(EntryDiff {
+        key,
+        change: EntryChange::Added,
+        original: None,
+      }),
+      Some(original_entry) if entry_changed(&original_entry, &current_entry) => Some(EntryDiff {
+        key,
+        change: EntryChange::Changed,
+        original: Some(original_entry),
+      }),
+      Some(_) => None,
+    })
+    .collect();
+
+  diffs.extend(
+    original
+      .iter()
+      .filter(|(key, _)| current.get(key).is_none())
+      .map(|(key, original_entry)| EntryDiff {
+        key,
+        change: EntryChange::Deleted,
+        original: Some(original_entry),
+      }),
+  );
+
+  diffs.sort_by(|a, b| {
+    a.key
+      .partial_cmp(b.key)
+      .unwrap_or(std::cmp::Ordering::Equal)
+  });
+  diffs
+}
+
+fn entry_changed(original: &SfoEntry, current: &SfoEntry) -> bool {
+  original.data != current.data
+    || original.index_table_entry.data_max_len != current.index_table_entry.data_max_len
+    || original.index_table_entry.data_format != current.index_table_entry.data_format
+}
+
+/// Restores a single entry of the current sfo to its state in the original sfo - an added entry
+/// is removed, while a changed or deleted one gets its original value, format and reserved size.
+/// The current sfo is left untouched when the entry could not be restored.
+pub fn revert_entry(original: &Sfo, current: &mut Sfo, key: &Keys) -> Result<(), String> {
+  let mut reverted = current.clone();
+  if reverted.get(key).is_some() {
+    reverted.delete(key)?;
+  }
+
+  if let Some(original_entry) = original.get_entry(key) {
+    reverted.add_with_layout(
+      key.clone(),
+      original_entry.data.clone(),
+      original_entry.index_table_entry.data_format,
+      original_entry.index_table_entry.data_max_len,
+    )?;
+  }
+
+  *current = reverted;
+  Ok(())
+}
+
+/// Lists every difference between two sfos, including header fields and index table entries, so
+/// a written file can be checked against the sfo it was exported from.
+pub fn mismatches(expected: &Sfo, actual: &Sfo) -> Vec<String> {
+  let mut mismatches = Vec::new();
+
+  if expected.magic != actual.magic {
+    mismatches.push(String::from("magic differs"));
+  }
+
+  if expected.header != actual.header {
+    mismatches.push(format!(
+      "header differs:\n{}\nexpected:\n{}",
+      actual.header, expected.header
+    ));
+  }
+
+  if expected.padding != actual.padding {
+    mismatches.push(format!(
+      "key table padding of {} bytes differs from expected {} bytes",
+      actual.padding, expected.padding
+    ));
+  }
+
+  for (key, expected_entry) in expected.iter() {
+    match actual.get_entry(key) {
+      None => mismatches.push(format!("entry {key} is missing")),
+      Some(actual_entry) => {
+        if actual_entry.data != expected_entry.data {
+          mismatches.push(format!(
+            "value of entry {key} \"{}\" differs from expected \"{}\"",
+            actual_entry.data, expected_entry.data
+          ));
+        }
+
+        if actual_entry.index_table_entry != expected_entry.index_table_entry {
+          mismatches.push(format!(
+            "index table entry of {key} differs:\n{}\nexpected:\n{}",
+            actual_entry.index_table_entry, expected_entry.index_table_entry
+          ));
+        }
+      }
+    }
+  }
+
+  mismatches.extend(
+    actual
+      .iter()
+      .filter(|(key, _)| expected.get(key).is_none())
+      .map(|(key, _)| format!("unexpected entry {key}")),
+  );
+
+  mismatches
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sfo::{
+    mapping::DataField,
+    test_fixtures::{exported, sample_sfo},
+  };
+
+  fn text(value: &str) -> DataField {
+    DataField::Utf8String(String::from(value))
+  }
+
+  fn changed_sfo(original: &Sfo) -> Sfo {
+    let mut current = original.clone();
+    current
+      .edit(&Keys::Title, text("Game of the Year"))
+      .unwrap();
+    current.delete(&Keys::TitleId).unwrap();
+    current.add(Keys::Detail, text("Description"));
+    current
+  }
+
+  #[test]
+  fn diff_lists_changes_in_key_order() {
+    let original = sample_sfo();
+    let current = changed_sfo(&original);
+
+    let changes: Vec<(String, EntryChange)> = diff(&original, &current)
+      .into_iter()
+      .map(|entry_diff| (entry_diff.key.to_string(), entry_diff.change))
+      .collect();
+    assert_eq!(
+      changes,
+      [
+        (String::from("DETAIL"), EntryChange::Added),
+        (String::from("TITLE"), EntryChange::Changed),
+        (String::from("TITLE_ID"), EntryChange::Deleted),
+      ]
+    );
+    assert!(diff(&original, &original.clone()).is_empty());
+  }
+
+  #[test]
+  fn diff_reports_resized_entry() {
+    let original = sample_sfo();
+    let mut current = original.clone();
+    current.resize(&Keys::TitleId, 32).unwrap();
+
+    let diffs = diff(&original, &current);
+    assert_eq!(diffs.len(), 1);
+    assert!(*diffs[0].key == Keys::TitleId);
+    assert_eq!(diffs[0].change, EntryChange::Changed);
+  }
+
+  #[test]
+  fn revert_entry_restores_original_file() {
+    let original = sample_sfo();
+    let mut current = changed_sfo(&original);
+
+    for key in [Keys::Detail, Keys::Title, Keys::TitleId] {
+      revert_entry(&original, &mut current, &key).unwrap();
+      assert!(
+        diff(&original, &current)
+          .iter()
+          .all(|entry_diff| *entry_diff.key != key)
+      );
+    }
+    assert_eq!(exported(&current), exported(&original));
+  }
+
+  #[test]
+  fn revert_entry_keeps_entry_when_it_cannot_be_restored() {
+    let mut original = sample_sfo();
+    original.index_table.entries[1].data_max_len = 2;
+    let mut current = changed_sfo(&sample_sfo());
+    let before = exported(&current);
+
+    assert!(revert_entry(&original, &mut current, &Keys::Title).is_err());
+    assert_eq!(exported(&current), before);
+    assert!(current.get(&Keys::Title) == Some(&text("Game of the Year")));
+  }
+
+  #[test]
+  fn mismatches_report_every_difference() {
+    let original = sample_sfo();
+    assert!(mismatches(&original, &original.clone()).is_empty());
+
+    let current = changed_sfo(&original);
+    let messages = mismatches(&original, &current);
+    assert!(
+      messages
+        .iter()
+        .any(|message| message.starts_with("header differs"))
+    );
+    assert!(
+      messages
+        .iter()
+        .any(|message| message.starts_with("value of entry TITLE "))
+    );
+    assert!(messages.contains(&String::from("entry TITLE_ID is missing")));
+    assert!(messages.contains(&String::from("unexpected entry DETAIL")));
+
+    let mut padded = original.clone();
+    padded.set_padding(original.padding + 4);
+    assert!(
+      mismatches(&original, &padded)
+        .iter()
+        .any(|message| message.starts_with("key table padding"))
+    );
+  }
+}
@@ -1,12 +1,42 @@
 use std::fmt::Display;
 
-#[derive(Clone, Copy, Debug)]
+use crate::sfo::mapping::DataField;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Format {
   Utf8Special = 0x00,
   Utf8 = 0x02,
   U32 = 0x04,
 }
 
+impl Format {
+  /// Short name of the format, as used by the `fmt` attribute of param.sfx.
+  pub fn short_name(&self) -> &'static str {
+    match self {
+      Format::Utf8Special => "utf8-s",
+      Format::Utf8 => "utf8",
+      Format::U32 => "int32",
+    }
+  }
+
+  pub fn from_short_name(name: &str) -> Option<Self> {
+    match name {
+      "utf8-s" => Some(Format::Utf8Special),
+      "utf8" => Some(Format::Utf8),
+      "int32" => Some(Format::U32),
+      _ => None,
+    }
+  }
+
+  pub fn accepts(&self, data_field: &DataField) -> bool {
+    matches!(
+      (self, data_field),
+      (Format::Utf8 | Format::Utf8Special, DataField::Utf8String(_))
+        | (Format::U32, DataField::U32(_))
+    )
+  }
+}
+
 impl From<Format> for [u8; 2] {
   fn from(val: Format) -> Self {
     [0x04, val as u8]
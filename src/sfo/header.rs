@@ -3,17 +3,76 @@ use std::{
   io::{self, Read, Write},
 };
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Header {
-  version: u32,
+  pub version: u32,
   pub key_table_start: u32,
   pub data_table_start: u32,
   pub table_entries: u32,
 }
 
-const KEY_TABLE_ENTRY_SIZE: u8 = 16;
+pub const KEY_TABLE_ENTRY_SIZE: u8 = 16;
+/// Size of the magic followed by the header fields.
+pub const HEADER_SIZE: u32 = 20;
+
+/// Known versions of the PSF format. Version is stored as major and minor bytes, followed by two
+/// unused bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, strum::EnumIter)]
+pub enum PsfVersion {
+  V1_0,
+  V1_1,
+}
+
+impl PsfVersion {
+  pub fn from_raw(version: u32) -> Option<Self> {
+    match version {
+      0x0001 => Some(PsfVersion::V1_0),
+      0x0101 => Some(PsfVersion::V1_1),
+      _ => None,
+    }
+  }
+
+  pub fn raw(&self) -> u32 {
+    match self {
+      PsfVersion::V1_0 => 0x0001,
+      PsfVersion::V1_1 => 0x0101,
+    }
+  }
+}
+
+impl Display for PsfVersion {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PsfVersion::V1_0 => write!(f, "1.0"),
+      PsfVersion::V1_1 => write!(f, "1.1"),
+    }
+  }
+}
 
 impl Header {
+  /// Header of a file without any entries.
+  pub fn empty(version: PsfVersion) -> Self {
+    Header {
+      version: version.raw(),
+      key_table_start: HEADER_SIZE,
+      data_table_start: HEADER_SIZE,
+      table_entries: 0,
+    }
+  }
+
+  pub fn psf_version(&self) -> Option<PsfVersion> {
+    PsfVersion::from_raw(self.version)
+  }
+
+  /// Version decoded as `major.minor`, regardless of whether it is known.
+  pub fn version_name(&self) -> String {
+    let [major, minor, ..] = self.version.to_le_bytes();
+    match self.psf_version() {
+      Some(psf_version) => psf_version.to_string(),
+      None => format!("{major}.{minor} (unknown)"),
+    }
+  }
+
   pub fn new<T>(reader: &mut T) -> Result<Self, String>
   where
     T: Read,
@@ -59,6 +118,17 @@ impl Header {
     self.table_entries -= 1;
   }
 
+  pub fn rename_entry(
+    &mut self,
+    prev_key_size: u32,
+    key_size: u32,
+    prev_padding: u32,
+    padding: u32,
+  ) {
+    self.data_table_start =
+      self.data_table_start - prev_key_size - prev_padding + key_size + padding;
+  }
+
   pub fn edit_entry(&mut self, prev_padding: u32, padding: u32) {
     self.data_table_start = self.data_table_start - prev_padding + padding;
   }
@@ -80,7 +150,8 @@ impl Display for Header {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(
       f,
-      "Version: {:#010X}\nKey table start offset: {:#010X}\nData table start offset: {:#010X}\nTable entries count: {} [{:#010X}]",
+      "Version: {} [{:#010X}]\nKey table start offset: {:#010X}\nData table start offset: {:#010X}\nTable entries count: {} [{:#010X}]",
+      self.version_name(),
       self.version,
       self.key_table_start,
       self.data_table_start,
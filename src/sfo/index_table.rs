@@ -9,6 +9,7 @@ use crate::sfo::{
   mapping::DataField,
 };
 
+#[derive(Clone, Default)]
 pub struct IndexTable {
   pub entries: Vec<IndexTableEntry>,
 }
@@ -40,15 +41,19 @@ impl IndexTable {
     Ok(())
   }
 
-  pub fn add(&mut self, idx: usize, key_len: u16, data_field: &DataField, all_keys_len: u16) {
+  pub fn add(&mut self, idx: usize, key_len: u16, new_entry: IndexTableEntry, all_keys_len: u16) {
     let (key_offset, data_offset) = self.offsets_for_idx(idx, all_keys_len);
 
-    let new_table_entry = IndexTableEntry::for_data_field(data_field, key_offset, data_offset);
+    let new_table_entry = IndexTableEntry {
+      key_offset,
+      data_offset,
+      ..new_entry
+    };
     self.entries.insert(idx, new_table_entry);
 
     for entry in self.entries[idx + 1..].iter_mut() {
       entry.key_offset += key_len;
-      entry.data_offset += data_field.len();
+      entry.data_offset += new_table_entry.data_max_len;
     }
   }
 
@@ -64,17 +69,27 @@ impl IndexTable {
     })
   }
 
+  /// Updates the entry to hold the data field. Reserved size of a text entry is kept as long as
+  /// the new value fits in it, otherwise it grows to the size of the value.
   pub fn edit(&mut self, idx: usize, data_field: &DataField) -> Result<(), String> {
-    let (prev_len, new_len) = {
+    let (prev_max_len, new_max_len) = {
       let entry = self.entries.get_mut(idx).ok_or(format!(
         "could not edit entry with index {idx} - no entry with such index"
       ))?;
-      let prev_len = entry.data_len;
+      let prev_max_len = entry.data_max_len;
       match data_field {
         DataField::Utf8String(text) => {
-          entry.data_len = (text.len() + 1) as u32;
-          entry.data_max_len = (text.len() + 1) as u32;
-          entry.data_format = Format::Utf8;
+          let data_len = match entry.data_format {
+            Format::Utf8Special => text.len() as u32,
+            Format::Utf8 => (text.len() + 1) as u32,
+            Format::U32 => {
+              entry.data_format = Format::Utf8;
+              entry.data_max_len = 0;
+              (text.len() + 1) as u32
+            }
+          };
+          entry.data_len = data_len;
+          entry.data_max_len = entry.data_max_len.max(data_len);
         }
         DataField::U32(_) => {
           entry.data_len = 4;
@@ -82,21 +97,55 @@ impl IndexTable {
           entry.data_format = Format::U32;
         }
       };
-      (prev_len, entry.data_len)
+      (prev_max_len, entry.data_max_len)
     };
 
     for entry in self.entries[idx + 1..].iter_mut() {
-      entry.data_offset = entry.data_offset - prev_len + new_len;
+      entry.data_offset = entry.data_offset - prev_max_len + new_max_len;
+    }
+
+    Ok(())
+  }
+
+  pub fn resize(&mut self, idx: usize, data_max_len: u32) -> Result<(), String> {
+    let entry = self.entries.get_mut(idx).ok_or(format!(
+      "could not resize entry with index {idx} - no entry with such index"
+    ))?;
+    if data_max_len < entry.data_len {
+      return Err(format!(
+        "reserved size of {data_max_len} bytes is smaller than data length of {} bytes",
+        entry.data_len
+      ));
+    }
+
+    let prev_max_len = entry.data_max_len;
+    entry.data_max_len = data_max_len;
+    for entry in self.entries[idx + 1..].iter_mut() {
+      entry.data_offset = entry.data_offset - prev_max_len + data_max_len;
     }
 
     Ok(())
   }
 
+  pub fn rename(&mut self, idx: usize, new_idx: usize, keys_lens: &[u16]) {
+    let entry = self.entries.remove(idx);
+    self.entries.insert(new_idx, entry);
+
+    let mut key_offset: u16 = 0;
+    let mut data_offset: u32 = 0;
+    for (entry, key_len) in self.entries.iter_mut().zip(keys_lens) {
+      entry.key_offset = key_offset;
+      entry.data_offset = data_offset;
+      key_offset += key_len;
+      data_offset += entry.data_max_len;
+    }
+  }
+
   pub fn delete(&mut self, idx: usize, key_len: u16) {
     let removed_entry = self.entries.remove(idx);
     for entry in self.entries[idx..].iter_mut() {
       entry.key_offset -= key_len;
-      entry.data_offset -= removed_entry.data_len;
+      entry.data_offset -= removed_entry.data_max_len;
     }
   }
 }
@@ -114,7 +163,7 @@ impl Display for IndexTable {
   }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct IndexTableEntry {
   pub key_offset: u16,
   pub data_format: Format,
@@ -1,4 +1,10 @@
-#[derive(strum::EnumString, PartialEq, Eq, strum::Display, strum::AsRefStr, Clone, Hash)]
+use crate::sfo::{format::Format, header::PsfVersion, mapping::DataField};
+
+const DATA_ALIGNMENT_BYTES: u32 = 4;
+
+#[derive(
+  strum::EnumString, strum::EnumIter, PartialEq, Eq, strum::Display, strum::AsRefStr, Clone, Hash,
+)]
 pub enum Keys {
   #[strum(serialize = "ACCOUNT_ID")]
   AccountId,
@@ -72,6 +78,46 @@ pub enum Keys {
   TargetAppVer,
   #[strum(serialize = "TITLE")]
   Title,
+  #[strum(serialize = "TITLE_00")]
+  Title00,
+  #[strum(serialize = "TITLE_01")]
+  Title01,
+  #[strum(serialize = "TITLE_02")]
+  Title02,
+  #[strum(serialize = "TITLE_03")]
+  Title03,
+  #[strum(serialize = "TITLE_04")]
+  Title04,
+  #[strum(serialize = "TITLE_05")]
+  Title05,
+  #[strum(serialize = "TITLE_06")]
+  Title06,
+  #[strum(serialize = "TITLE_07")]
+  Title07,
+  #[strum(serialize = "TITLE_08")]
+  Title08,
+  #[strum(serialize = "TITLE_09")]
+  Title09,
+  #[strum(serialize = "TITLE_10")]
+  Title10,
+  #[strum(serialize = "TITLE_11")]
+  Title11,
+  #[strum(serialize = "TITLE_12")]
+  Title12,
+  #[strum(serialize = "TITLE_13")]
+  Title13,
+  #[strum(serialize = "TITLE_14")]
+  Title14,
+  #[strum(serialize = "TITLE_15")]
+  Title15,
+  #[strum(serialize = "TITLE_16")]
+  Title16,
+  #[strum(serialize = "TITLE_17")]
+  Title17,
+  #[strum(serialize = "TITLE_18")]
+  Title18,
+  #[strum(serialize = "TITLE_19")]
+  Title19,
   #[strum(serialize = "TITLE_ID")]
   TitleId,
   #[strum(serialize = "TITLE_XX")]
@@ -90,6 +136,206 @@ impl Keys {
   pub fn len(&self) -> usize {
     self.to_string().len() + 1
   }
+
+  pub fn is_localized_title(&self) -> bool {
+    matches!(
+      self,
+      Keys::Title00
+        | Keys::Title01
+        | Keys::Title02
+        | Keys::Title03
+        | Keys::Title04
+        | Keys::Title05
+        | Keys::Title06
+        | Keys::Title07
+        | Keys::Title08
+        | Keys::Title09
+        | Keys::Title10
+        | Keys::Title11
+        | Keys::Title12
+        | Keys::Title13
+        | Keys::Title14
+        | Keys::Title15
+        | Keys::Title16
+        | Keys::Title17
+        | Keys::Title18
+        | Keys::Title19
+    )
+  }
+
+  /// Format in which the console expects the value of the key. `None` when the key is not known.
+  pub fn format(&self) -> Option<Format> {
+    let format = match self {
+      Keys::AccountId
+      | Keys::Padding
+      | Keys::Params
+      | Keys::Params2
+      | Keys::SavedataFileList
+      | Keys::SavedataParams => Format::Utf8Special,
+      Keys::AnalogMode
+      | Keys::Attribute
+      | Keys::Bootable
+      | Keys::ItemPriority
+      | Keys::Lang
+      | Keys::ParentalLevelX
+      | Keys::ParentalLevel
+      | Keys::Parantallevel
+      | Keys::RegionDeny
+      | Keys::Resolution
+      | Keys::SoundFormat
+      | Keys::Source
+      | Keys::XmbApps => Format::U32,
+      Keys::Unknown(_) => return None,
+      _ => Format::Utf8,
+    };
+
+    Some(format)
+  }
+
+  /// Commonly used values of the key together with their description.
+  pub fn suggested_values(&self) -> Vec<(DataField, &'static str)> {
+    match self {
+      Keys::Bootable => vec![(DataField::U32(1), "Bootable")],
+      Keys::Category => [
+        ("DG", "Disc Game"),
+        ("HG", "HDD Game"),
+        ("GD", "Game Data"),
+        ("SD", "Save Data"),
+        ("AP", "Application Photo"),
+        ("AM", "Application Music"),
+        ("AV", "Application Video"),
+        ("AT", "Application TV"),
+        ("BV", "Broadcast Video"),
+        ("CB", "Network Application"),
+        ("HM", "PlayStation Home"),
+        ("IP", "Install Package"),
+        ("1P", "PS1 Classic"),
+        ("2P", "PS2 Classic"),
+        ("MN", "PSP minis"),
+        ("PP", "PSP"),
+        ("PE", "PSP Remaster"),
+      ]
+      .into_iter()
+      .map(|(code, description)| (DataField::Utf8String(String::from(code)), description))
+      .collect(),
+      Keys::AppVer | Keys::Version => {
+        vec![(
+          DataField::Utf8String(String::from("01.00")),
+          "Initial version",
+        )]
+      }
+      Keys::Resolution => vec![(DataField::U32(0x3F), "All resolutions")],
+      Keys::SoundFormat => vec![(DataField::U32(0x01), "LPCM 2.0")],
+      Keys::ParentalLevel => vec![(DataField::U32(0), "No parental restriction")],
+      _ => vec![],
+    }
+  }
+
+  /// Oldest version of the PSF format in which the key is expected. Files of older versions may
+  /// still hold the key, so it is only a hint.
+  pub fn min_version(&self) -> PsfVersion {
+    match self {
+      Keys::AccountId
+      | Keys::ItemPriority
+      | Keys::NpCommunicationId
+      | Keys::Params
+      | Keys::Params2
+      | Keys::Ps3SystemVer
+      | Keys::Resolution
+      | Keys::SavedataListParam
+      | Keys::SoundFormat
+      | Keys::TargetAppVer
+      | Keys::XmbApps => PsfVersion::V1_1,
+      key if key.is_localized_title() => PsfVersion::V1_1,
+      _ => PsfVersion::V1_0,
+    }
+  }
+
+  /// Whether the numeric value of the key is a set of bit flags rather than a quantity.
+  pub fn is_bitmask(&self) -> bool {
+    matches!(
+      self,
+      Keys::Attribute
+        | Keys::ParentalLevelX
+        | Keys::RegionDeny
+        | Keys::Resolution
+        | Keys::SoundFormat
+    )
+  }
+
+  /// Whether the value of the key is a description text which may span several lines.
+  pub fn is_multiline(&self) -> bool {
+    matches!(self, Keys::Detail | Keys::SavedataDetail)
+  }
+
+  /// Whether the key only names a family of keys, such as TITLE_XX standing for TITLE_00 to
+  /// TITLE_19, and never appears in a file itself.
+  pub fn is_placeholder(&self) -> bool {
+    matches!(
+      self,
+      Keys::TitleXx | Keys::Titleid0xx | Keys::ParentalLevelX
+    )
+  }
+
+  /// Maximum size in bytes (including null terminator for UTF-8 values) reserved for the key
+  /// by the console. `None` when the limit of the key is not known.
+  pub fn max_len(&self) -> Option<u32> {
+    let max_len = match self {
+      Keys::AnalogMode
+      | Keys::Attribute
+      | Keys::Bootable
+      | Keys::ItemPriority
+      | Keys::Lang
+      | Keys::ParentalLevelX
+      | Keys::ParentalLevel
+      | Keys::Parantallevel
+      | Keys::RegionDeny
+      | Keys::Resolution
+      | Keys::SoundFormat
+      | Keys::Source
+      | Keys::XmbApps
+      | Keys::Category => 4,
+      Keys::AppVer
+      | Keys::Padding
+      | Keys::Ps3SystemVer
+      | Keys::SavedataListParam
+      | Keys::TargetAppVer
+      | Keys::Version => 8,
+      Keys::Params2 => 12,
+      Keys::AccountId
+      | Keys::Accountid
+      | Keys::NpCommunicationId
+      | Keys::NpCommId
+      | Keys::TitleId
+      | Keys::Titleid0xx => 16,
+      Keys::GamedataId | Keys::PatchFile => 32,
+      Keys::ContentId => 48,
+      Keys::SavedataDirectory => 64,
+      Keys::SavedataParams | Keys::SavedataTitle | Keys::SubTitle | Keys::Title | Keys::TitleXx => {
+        128
+      }
+      key if key.is_localized_title() => 128,
+      Keys::License => 512,
+      Keys::Detail | Keys::Params | Keys::SavedataDetail => 1024,
+      Keys::SavedataFileList => 3168,
+      _ => return None,
+    };
+
+    Some(max_len)
+  }
+
+  /// Size to reserve for the value stored in the format - the size reserved by the console for
+  /// the key when the value fits in it, or the length of the value aligned to 4 bytes otherwise.
+  pub fn default_max_len(&self, data_field: &DataField, format: Format) -> u32 {
+    let data_len = data_field.len_in(format);
+    match format {
+      Format::U32 => data_len,
+      Format::Utf8 | Format::Utf8Special => self
+        .max_len()
+        .filter(|max_len| *max_len >= data_len)
+        .unwrap_or(data_len.next_multiple_of(DATA_ALIGNMENT_BYTES)),
+    }
+  }
 }
 
 impl PartialOrd for Keys {
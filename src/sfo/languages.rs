@@ -0,0 +1,213 @@
+use std::{
+  io::{Read, Write},
+  str::FromStr,
+};
+
+use crate::sfo::{Sfo, keys::Keys, mapping::DataField};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, strum::Display, strum::EnumIter)]
+pub enum Language {
+  #[strum(serialize = "Japanese")]
+  Japanese = 0,
+  #[strum(serialize = "English (US)")]
+  EnglishUs = 1,
+  #[strum(serialize = "French")]
+  French = 2,
+  #[strum(serialize = "Spanish")]
+  Spanish = 3,
+  #[strum(serialize = "German")]
+  German = 4,
+  #[strum(serialize = "Italian")]
+  Italian = 5,
+  #[strum(serialize = "Dutch")]
+  Dutch = 6,
+  #[strum(serialize = "Portuguese (PT)")]
+  PortuguesePt = 7,
+  #[strum(serialize = "Russian")]
+  Russian = 8,
+  #[strum(serialize = "Korean")]
+  Korean = 9,
+  #[strum(serialize = "Chinese (Traditional)")]
+  ChineseTraditional = 10,
+  #[strum(serialize = "Chinese (Simplified)")]
+  ChineseSimplified = 11,
+  #[strum(serialize = "Finnish")]
+  Finnish = 12,
+  #[strum(serialize = "Swedish")]
+  Swedish = 13,
+  #[strum(serialize = "Danish")]
+  Danish = 14,
+  #[strum(serialize = "Norwegian")]
+  Norwegian = 15,
+  #[strum(serialize = "Polish")]
+  Polish = 16,
+  #[strum(serialize = "Portuguese (BR)")]
+  PortugueseBr = 17,
+  #[strum(serialize = "English (UK)")]
+  EnglishUk = 18,
+  #[strum(serialize = "Turkish")]
+  Turkish = 19,
+}
+
+const TITLES_CSV_HEADER: [&str; 3] = ["code", "language", "title"];
+
+impl Language {
+  pub fn code(&self) -> u8 {
+    *self as u8
+  }
+
+  pub fn title_key(&self) -> Keys {
+    Keys::from_str(&format!("TITLE_{:02}", self.code()))
+      .unwrap_or_else(|_| Keys::Unknown(format!("TITLE_{:02}", self.code())))
+  }
+
+  pub fn from_code(code: u8) -> Option<Self> {
+    <Self as strum::IntoEnumIterator>::iter().find(|lang| lang.code() == code)
+  }
+}
+
+pub fn localized_title(sfo: &Sfo, language: Language) -> Option<&DataField> {
+  sfo.get(&language.title_key())
+}
+
+/// Sets, replaces or (with `None`) removes the `TITLE_xx` entry of the language. Titles longer
+/// than the maximum size of the key are rejected. Returns whether the sfo has been modified.
+pub fn set_localized_title(
+  sfo: &mut Sfo,
+  language: Language,
+  title: Option<String>,
+) -> Result<bool, String> {
+  let key = language.title_key();
+  let current = sfo.get(&key).map(|title| title.to_string());
+  if let Some(title) = &title
+    && let Some(max_len) = key.max_len()
+  {
+    let title_len = DataField::Utf8String(title.clone()).len();
+    if title_len > max_len {
+      return Err(format!(
+        "{language} title takes {title_len} bytes which exceeds maximum of {max_len} bytes for key {key}"
+      ));
+    }
+  }
+
+  match (current, title) {
+    (None, None) => Ok(false),
+    (Some(_), None) => sfo.delete(&key).map(|_| true),
+    (None, Some(title)) => {
+      sfo.add(key, DataField::Utf8String(title));
+      Ok(true)
+    }
+    (Some(current), Some(title)) if current == title => Ok(false),
+    (Some(_), Some(title)) => sfo.edit(&key, DataField::Utf8String(title)).map(|_| true),
+  }
+}
+
+pub fn export_titles_csv<T>(sfo: &Sfo, writer: &mut T) -> Result<(), String>
+where
+  T: Write,
+{
+  let mut csv_writer = csv::Writer::from_writer(writer);
+  csv_writer
+    .write_record(TITLES_CSV_HEADER)
+    .map_err(|err| format!("could not write csv header: {err}"))?;
+
+  for language in <Language as strum::IntoEnumIterator>::iter() {
+    let title = localized_title(sfo, language).map_or(String::new(), |title| title.to_string());
+    csv_writer
+      .write_record([
+        format!("{:02}", language.code()),
+        language.to_string(),
+        title,
+      ])
+      .map_err(|err| format!("could not write csv row for language {language}: {err}"))?;
+  }
+
+  csv_writer
+    .flush()
+    .map_err(|err| format!("could not flush csv writer: {err}"))
+}
+
+/// Reads titles grid exported with `export_titles_csv`. Languages with an empty title are
+/// returned as `None`, meaning their `TITLE_xx` entry should be removed.
+pub fn import_titles_csv<T>(reader: &mut T) -> Result<Vec<(Language, Option<String>)>, String>
+where
+  T: Read,
+{
+  let mut csv_reader = csv::Reader::from_reader(reader);
+  let mut titles = Vec::new();
+
+  for (idx, record) in csv_reader.records().enumerate() {
+    let record = record.map_err(|err| format!("could not read csv row {}: {err}", idx + 1))?;
+    let code = record
+      .get(0)
+      .ok_or_else(|| format!("csv row {} is missing language code", idx + 1))?;
+    let language = code
+      .trim()
+      .parse::<u8>()
+      .ok()
+      .and_then(Language::from_code)
+      .ok_or_else(|| format!("csv row {} has unknown language code \"{code}\"", idx + 1))?;
+    let title = record
+      .get(2)
+      .filter(|title| !title.is_empty())
+      .map(String::from);
+
+    titles.push((language, title));
+  }
+
+  Ok(titles)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use super::*;
+  use crate::sfo::header::PsfVersion;
+
+  #[test]
+  fn set_localized_title_rejects_titles_over_max_len() {
+    let mut sfo = Sfo::empty(PsfVersion::V1_1);
+    let max_len = Language::French.title_key().max_len().unwrap() as usize;
+
+    assert!(set_localized_title(&mut sfo, Language::French, Some("a".repeat(max_len))).is_err());
+    assert!(sfo.get(&Language::French.title_key()).is_none());
+    assert!(
+      set_localized_title(&mut sfo, Language::French, Some("a".repeat(max_len - 1))).unwrap()
+    );
+  }
+
+  #[test]
+  fn titles_csv_round_trip() {
+    let mut sfo = Sfo::empty(PsfVersion::V1_1);
+    set_localized_title(&mut sfo, Language::Japanese, Some(String::from("ゲーム"))).unwrap();
+    set_localized_title(
+      &mut sfo,
+      Language::German,
+      Some(String::from("Spiel, \"neu\"")),
+    )
+    .unwrap();
+    let mut csv = Vec::new();
+    export_titles_csv(&sfo, &mut csv).unwrap();
+
+    let titles = import_titles_csv(&mut Cursor::new(csv)).unwrap();
+    let mut imported = Sfo::empty(PsfVersion::V1_1);
+    for (language, title) in titles {
+      set_localized_title(&mut imported, language, title).unwrap();
+    }
+
+    for language in <Language as strum::IntoEnumIterator>::iter() {
+      assert_eq!(
+        localized_title(&imported, language).map(DataField::to_string),
+        localized_title(&sfo, language).map(DataField::to_string),
+      );
+    }
+  }
+
+  #[test]
+  fn import_titles_csv_rejects_unknown_language() {
+    let csv = "code,language,title\n00,Japanese,Game\n42,Klingon,Game\n";
+    let err = import_titles_csv(&mut Cursor::new(csv)).err().unwrap();
+    assert_eq!(err, "csv row 2 has unknown language code \"42\"");
+  }
+}
@@ -0,0 +1,191 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  str::FromStr,
+};
+
+use serde::Deserialize;
+use toml::{Spanned, Table, Value};
+
+use crate::sfo::{
+  Sfo, format::Format, header::PsfVersion, keys::Keys, mapping::DataField, param_sfx::line_at,
+  validation::validate,
+};
+
+/// Manifest describing PARAM.SFO to build. Values of `params` are either a string or an integer,
+/// or a table with the value and its optional type (utf8, utf8-s or int32) and max_len:
+///
+/// ```toml
+/// version = "1.1"
+///
+/// [params]
+/// TITLE = "Homebrew"
+/// ATTRIBUTE = 0
+/// DETAIL = { value = "Built in CI", type = "utf8", max_len = 1024 }
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+  version: Option<Spanned<String>>,
+  params: BTreeMap<String, Spanned<Value>>,
+}
+
+/// Builds the sfo described by the TOML manifest. Errors point at the line of the manifest, and
+/// the built sfo has to pass validation - validation warnings do not fail the build.
+pub fn build_from_manifest(manifest: &str) -> Result<Sfo, String> {
+  let parsed: Manifest =
+    toml::from_str(manifest).map_err(|err| format!("could not parse manifest: {err}"))?;
+
+  let version = match &parsed.version {
+    None => PsfVersion::V1_1,
+    Some(version) => match version.get_ref().as_str() {
+      "1.0" => PsfVersion::V1_0,
+      "1.1" => PsfVersion::V1_1,
+      other => {
+        return Err(format!(
+          "line {}: unknown version \"{other}\", expected \"1.0\" or \"1.1\"",
+          line_at(manifest, version.span().start)
+        ));
+      }
+    },
+  };
+
+  let mut sfo = Sfo::empty(version);
+  let mut key_lines: HashMap<Keys, usize> = HashMap::new();
+  for (name, value) in parsed.params {
+    let line = line_at(manifest, value.span().start);
+    let key = Keys::from_str(&name).unwrap_or_else(|_| Keys::Unknown(name.clone()));
+    add_param(&mut sfo, key.clone(), value.get_ref())
+      .map_err(|err| format!("line {line}: {err}"))?;
+    key_lines.insert(key, line);
+  }
+
+  let issues: Vec<String> = validate(&sfo)
+    .into_iter()
+    .filter(|issue| issue.is_error())
+    .map(
+      |issue| match issue.key.and_then(|key| key_lines.get(&key)) {
+        Some(line) => format!("line {line}: {}", issue.message),
+        None => issue.message,
+      },
+    )
+    .collect();
+  match issues.is_empty() {
+    true => Ok(sfo),
+    false => Err(format!("built file is not valid:\n{}", issues.join("\n"))),
+  }
+}
+
+fn add_param(sfo: &mut Sfo, key: Keys, value: &Value) -> Result<(), String> {
+  let (value, format, max_len) = match value {
+    Value::Table(table) => param_table(&key, table)?,
+    value => (value, None, None),
+  };
+
+  let format = format.or_else(|| key.format()).unwrap_or(match value {
+    Value::Integer(_) => Format::U32,
+    _ => Format::Utf8,
+  });
+  let data_field = match (format, value) {
+    (Format::U32, Value::Integer(number)) => u32::try_from(*number)
+      .map(DataField::U32)
+      .map_err(|_| format!("value {number} of key {key} is not an unsigned 32-bit integer"))?,
+    (Format::U32, _) => {
+      return Err(format!("key {key} of type int32 requires an integer value"));
+    }
+    (Format::Utf8 | Format::Utf8Special, Value::String(text)) => {
+      DataField::Utf8String(text.clone())
+    }
+    (Format::Utf8 | Format::Utf8Special, _) => {
+      return Err(format!(
+        "key {key} of type {} requires a string value",
+        format.short_name()
+      ));
+    }
+  };
+
+  let max_len = max_len.unwrap_or_else(|| key.default_max_len(&data_field, format));
+  sfo.add_with_layout(key, data_field, format, max_len)
+}
+
+fn param_table<'a>(
+  key: &Keys,
+  table: &'a Table,
+) -> Result<(&'a Value, Option<Format>, Option<u32>), String> {
+  let mut value = None;
+  let mut format = None;
+  let mut max_len = None;
+
+  for (field, field_value) in table {
+    match (field.as_str(), field_value) {
+      ("value", field_value) => value = Some(field_value),
+      ("type", Value::String(name)) => {
+        format = Some(Format::from_short_name(name).ok_or_else(|| {
+          format!("unknown type \"{name}\" of key {key}, expected utf8, utf8-s or int32")
+        })?)
+      }
+      ("max_len", Value::Integer(number)) => {
+        max_len = Some(u32::try_from(*number).map_err(|_| {
+          format!("max_len {number} of key {key} is not an unsigned 32-bit integer")
+        })?)
+      }
+      ("type", _) => return Err(format!("type of key {key} has to be a string")),
+      ("max_len", _) => return Err(format!("max_len of key {key} has to be an integer")),
+      (field, _) => {
+        return Err(format!(
+          "unknown field \"{field}\" of key {key}, expected value, type or max_len"
+        ));
+      }
+    }
+  }
+
+  let value = value.ok_or_else(|| format!("key {key} is missing value"))?;
+  Ok((value, format, max_len))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn build_err(manifest: &str) -> String {
+    build_from_manifest(manifest)
+      .err()
+      .expect("build should fail")
+  }
+
+  #[test]
+  fn builds_params_with_default_and_explicit_layout() {
+    let sfo = build_from_manifest(
+      "version = \"1.1\"\n\n[params]\nTITLE = \"Homebrew\"\nATTRIBUTE = 0\nDETAIL = { value = \"Built in CI\", type = \"utf8\", max_len = 1024 }\n",
+    )
+    .unwrap();
+
+    let title = sfo.get_entry(&Keys::Title).unwrap();
+    assert_eq!(title.index_table_entry.data_max_len, 128);
+    let detail = sfo.get_entry(&Keys::Detail).unwrap();
+    assert_eq!(detail.index_table_entry.data_max_len, 1024);
+    assert!(matches!(sfo.get(&Keys::Attribute), Some(DataField::U32(0))));
+  }
+
+  #[test]
+  fn reports_the_line_of_an_unknown_version() {
+    let err = build_err("\nversion = \"2.0\"\n\n[params]\nTITLE = \"Homebrew\"\n");
+    assert!(err.starts_with("line 2:"), "{err}");
+  }
+
+  #[test]
+  fn reports_the_line_of_an_invalid_param() {
+    let err = build_err("[params]\nTITLE = \"Homebrew\"\nATTRIBUTE = -1\n");
+    assert!(err.starts_with("line 3:"), "{err}");
+
+    let err = build_err("[params]\nTITLE = { value = \"Homebrew\", type = \"text\" }\n");
+    assert!(err.starts_with("line 2:"), "{err}");
+  }
+
+  #[test]
+  fn reports_the_line_of_a_key_failing_validation() {
+    let err = build_err(
+      "[params]\nTITLE = \"Homebrew\"\nPS3_SYSTEM_VER = { value = 5, type = \"int32\" }\n",
+    );
+    assert!(err.contains("\nline 3:"), "{err}");
+  }
+}
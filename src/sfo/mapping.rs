@@ -8,6 +8,7 @@ use std::{
 
 use crate::sfo::{format::Format, header::Header, index_table::IndexTable, keys::Keys};
 
+#[derive(Clone, Default)]
 pub struct Mapping {
   keys_order: Vec<Keys>,
   entries: HashMap<Keys, DataField>,
@@ -61,10 +62,21 @@ impl Mapping {
         .map_err(|err| format!("could not read data entry with idx {idx} for key {key}: {err}"))?;
 
       let data: DataField = match index_table_entry.data_format {
-        Format::Utf8 | Format::Utf8Special => DataField::Utf8String(
-          String::from_utf8(data_buff)
-            .map_err(|err| format!("could not map UTF8 string: {err}"))?,
-        ),
+        Format::Utf8 | Format::Utf8Special => {
+          // Only data_len bytes hold the value, the rest of the reserved space is filled with
+          // zeroes, and utf8 values end with a null terminator.
+          let text_len = match index_table_entry.data_format {
+            Format::Utf8 => index_table_entry.data_len.saturating_sub(1),
+            _ => index_table_entry.data_len,
+          }
+          .min(index_table_entry.data_max_len) as usize;
+          data_buff.truncate(text_len);
+
+          DataField::Utf8String(
+            String::from_utf8(data_buff)
+              .map_err(|err| format!("could not map UTF8 string: {err}"))?,
+          )
+        }
         Format::U32 => DataField::U32(u32::from_le_bytes(
           data_buff[0..4]
             .try_into()
@@ -112,6 +124,19 @@ impl Mapping {
       .and_modify(|e| *e = data_field);
   }
 
+  /// Moves the entry under a new key to its sorted position, returning the new index.
+  pub fn rename(&mut self, idx: usize, key: &Keys, new_key: Keys) -> Result<usize, String> {
+    let data_field = self
+      .entries
+      .remove(key)
+      .ok_or_else(|| format!("could not find entry for key {key}"))?;
+    self.keys_order.remove(idx);
+
+    let new_idx = self.get_sorted_idx(&new_key);
+    self.add(new_idx, new_key, data_field);
+    Ok(new_idx)
+  }
+
   pub fn delete(&mut self, idx: usize, key: &Keys) {
     self.keys_order.remove(idx);
     self.entries.remove(key);
@@ -169,6 +194,7 @@ impl Mapping {
   }
 }
 
+#[derive(Clone, PartialEq)]
 pub enum DataField {
   Utf8String(String),
   U32(u32),
@@ -190,6 +216,27 @@ impl DataField {
       DataField::U32(_) => 4,
     }
   }
+
+  /// Length of the value when stored in the provided format.
+  pub fn len_in(&self, format: Format) -> u32 {
+    match (self, format) {
+      (DataField::Utf8String(val), Format::Utf8Special) => val.len() as u32,
+      _ => self.len(),
+    }
+  }
+
+  /// Converts the value to the other data type - a number to its decimal string, and a string
+  /// to a number when it holds one.
+  pub fn converted(&self) -> Result<DataField, String> {
+    match self {
+      DataField::U32(val) => Ok(DataField::Utf8String(val.to_string())),
+      DataField::Utf8String(text) => text
+        .trim()
+        .parse::<u32>()
+        .map(DataField::U32)
+        .map_err(|err| format!("value \"{text}\" is not an unsigned 32-bit integer: {err}")),
+    }
+  }
 }
 
 pub fn key_from_buff(buff: &[u8]) -> Result<Keys, String> {
@@ -231,3 +278,45 @@ impl<'a> Iterator for MappingIter<'a> {
     Some((elem_key, elem))
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use crate::sfo::{
+    Sfo,
+    format::Format,
+    test_fixtures::{PsfEntry, psf_bytes},
+  };
+
+  #[test]
+  fn text_is_read_up_to_its_data_length() {
+    let bytes = psf_bytes(&[
+      PsfEntry {
+        key: "CATEGORY",
+        format: Format::Utf8Special,
+        data: b"HG",
+        max_len: 4,
+      },
+      PsfEntry {
+        key: "TITLE",
+        format: Format::Utf8,
+        data: b"Game\0",
+        max_len: 8,
+      },
+    ]);
+    let sfo = Sfo::new(&mut Cursor::new(bytes)).unwrap();
+
+    let values: Vec<(String, String)> = sfo
+      .iter()
+      .map(|(key, entry)| (key.to_string(), entry.data.to_string()))
+      .collect();
+    assert_eq!(
+      values,
+      [
+        (String::from("CATEGORY"), String::from("HG")),
+        (String::from("TITLE"), String::from("Game")),
+      ]
+    );
+  }
+}
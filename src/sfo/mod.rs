@@ -5,24 +5,34 @@ use std::{
 use thiserror::Error;
 
 use crate::sfo::{
-  header::Header,
+  format::Format,
+  header::{Header, PsfVersion},
   index_table::{IndexTable, IndexTableEntry},
   keys::Keys,
   mapping::{DataField, Mapping, MappingIter},
 };
 
+pub mod diff;
 pub mod format;
 pub mod header;
 pub mod index_table;
 pub mod keys;
+pub mod languages;
+pub mod manifest;
 pub mod mapping;
+pub mod param_sfx;
+#[cfg(test)]
+pub mod test_fixtures;
+pub mod validation;
 
+#[derive(Clone)]
 pub struct Sfo {
   pub magic: [u8; 4],
   pub header: Header,
   pub index_table: IndexTable,
   pub entries_mapping: Mapping,
   pub padding: u32,
+  padding_override: Option<u32>,
 }
 
 const UNCONTAINED_PARAM_SFO_MAGIC: [u8; 4] = [0x00, 0x50, 0x53, 0x46];
@@ -63,16 +73,35 @@ impl Sfo {
     let index_table = IndexTable::new(reader, &header).map_err(SfoParseErr::IndexTableReadErr)?;
     let entries_mapping =
       Mapping::new(reader, &index_table, &header).map_err(SfoParseErr::EntriesMappingReadErr)?;
-    let mut sfo = Self {
+    let padding = header
+      .data_table_start
+      .checked_sub(header.key_table_start + entries_mapping.keys_len() as u32)
+      .ok_or_else(|| {
+        SfoParseErr::EntriesMappingReadErr(String::from(
+          "keys overlap with the data table start offset",
+        ))
+      })?;
+
+    Ok(Self {
       magic,
       header,
       index_table,
       entries_mapping,
-      padding: 0,
-    };
+      padding,
+      padding_override: None,
+    })
+  }
 
-    sfo.recalculate_padding();
-    Ok(sfo)
+  /// Sfo without any entries, in the provided version of the format.
+  pub fn empty(version: PsfVersion) -> Self {
+    Self {
+      magic: UNCONTAINED_PARAM_SFO_MAGIC,
+      header: Header::empty(version),
+      index_table: Default::default(),
+      entries_mapping: Default::default(),
+      padding: 0,
+      padding_override: None,
+    }
   }
 
   pub fn export<T>(&self, writer: &mut T) -> Result<(), io::Error>
@@ -91,13 +120,57 @@ impl Sfo {
   }
 
   pub fn add(&mut self, key: Keys, data_field: DataField) {
+    let index_table_entry = IndexTableEntry::for_data_field(&data_field, 0, 0);
+    self.insert(key, data_field, index_table_entry);
+  }
+
+  /// Adds the entry stored in the provided format with the provided reserved size, e.g. to
+  /// recreate an entry exactly as it was in another file.
+  pub fn add_with_layout(
+    &mut self,
+    key: Keys,
+    data_field: DataField,
+    data_format: Format,
+    data_max_len: u32,
+  ) -> Result<(), String> {
+    if self.get_idx(&key).is_ok() {
+      return Err(format!(
+        "could not add key {key} - entry with such key already exists"
+      ));
+    }
+
+    if !data_format.accepts(&data_field) {
+      return Err(format!(
+        "could not add key {key} - value does not match format \"{data_format}\""
+      ));
+    }
+
+    let data_len = data_field.len_in(data_format);
+    if data_len > data_max_len {
+      return Err(format!(
+        "could not add key {key} - value takes {data_len} bytes which exceeds reserved size of {data_max_len} bytes"
+      ));
+    }
+
+    let index_table_entry = IndexTableEntry {
+      key_offset: 0,
+      data_format,
+      data_len,
+      data_max_len,
+      data_offset: 0,
+    };
+    self.insert(key, data_field, index_table_entry);
+    Ok(())
+  }
+
+  fn insert(&mut self, key: Keys, data_field: DataField, index_table_entry: IndexTableEntry) {
     let sorted_idx = self.entries_mapping.get_sorted_idx(&key);
 
     let key_len = key.len();
     let all_keys_len = self.entries_mapping.keys_len() as u16;
     self
       .index_table
-      .add(sorted_idx, key_len as u16, &data_field, all_keys_len);
+      .add(sorted_idx, key_len as u16, index_table_entry, all_keys_len);
     self.entries_mapping.add(sorted_idx, key, data_field);
     let old_padding = self.padding;
     self.recalculate_padding();
@@ -108,6 +181,47 @@ impl Sfo {
 
   pub fn edit(&mut self, key: &Keys, data_field: DataField) -> Result<(), String> {
     let idx = self.get_idx(key)?;
+    let data_format = self
+      .index_table
+      .entries
+      .get(idx)
+      .map(|entry| entry.data_format)
+      .ok_or_else(|| format!("could not find index table entry of key {key}"))?;
+    if !data_format.accepts(&data_field) {
+      return Err(format!(
+        "could not edit key {key} - value does not match format \"{data_format}\", convert the type of the entry first"
+      ));
+    }
+
+    self.update(idx, key, data_field)
+  }
+
+  /// Returns the value of the entry converted to the other data type, as it would be stored
+  /// by `convert`.
+  pub fn convert_preview(&self, key: &Keys) -> Result<DataField, String> {
+    let data_field = self
+      .get(key)
+      .ok_or_else(|| format!("could not find entry with key {key}"))?;
+    let converted = data_field.converted()?;
+
+    if let Some(format) = key.format()
+      && !format.accepts(&converted)
+    {
+      return Err(format!(
+        "key {key} can only hold values of format \"{format}\""
+      ));
+    }
+
+    Ok(converted)
+  }
+
+  pub fn convert(&mut self, key: &Keys) -> Result<(), String> {
+    let converted = self.convert_preview(key)?;
+    let idx = self.get_idx(key)?;
+    self.update(idx, key, converted)
+  }
+
+  fn update(&mut self, idx: usize, key: &Keys, data_field: DataField) -> Result<(), String> {
     self.index_table.edit(idx, &data_field)?;
     self.entries_mapping.edit(key, data_field);
     let old_padding = self.padding;
@@ -128,6 +242,93 @@ impl Sfo {
     Ok(())
   }
 
+  /// Moves the entry under a new key, keeping its data, format and reserved size.
+  pub fn rename(&mut self, key: &Keys, new_key: Keys) -> Result<(), String> {
+    if self.get_idx(&new_key).is_ok() {
+      return Err(format!(
+        "could not rename key {key} to {new_key} - entry with such key already exists"
+      ));
+    }
+
+    let idx = self.get_idx(key)?;
+    let new_key_len = new_key.len();
+    let new_idx = self.entries_mapping.rename(idx, key, new_key)?;
+    let keys_lens: Vec<u16> = self
+      .entries_mapping
+      .iter()
+      .map(|(key, _)| key.len() as u16)
+      .collect();
+    self.index_table.rename(idx, new_idx, &keys_lens);
+    let old_padding = self.padding;
+    self.recalculate_padding();
+    self.header.rename_entry(
+      key.len() as u32,
+      new_key_len as u32,
+      old_padding,
+      self.padding,
+    );
+    Ok(())
+  }
+
+  /// Moves the entry under a new key and sets its value, leaving the sfo unchanged when either
+  /// step fails.
+  pub fn rename_and_edit(
+    &mut self,
+    key: &Keys,
+    new_key: Keys,
+    data_field: DataField,
+  ) -> Result<(), String> {
+    let mut edited = self.clone();
+    edited.rename(key, new_key.clone())?;
+    edited.edit(&new_key, data_field)?;
+    *self = edited;
+    Ok(())
+  }
+
+  /// Changes the reserved size of the entry, shifting data of the following entries.
+  pub fn resize(&mut self, key: &Keys, data_max_len: u32) -> Result<(), String> {
+    let idx = self.get_idx(key)?;
+    self
+      .index_table
+      .resize(idx, data_max_len)
+      .map_err(|err| format!("could not resize key {key}: {err}"))
+  }
+
+  /// Overrides padding between the key and data tables. The override is kept through later
+  /// changes of entries, even when it leaves the data table unaligned.
+  pub fn set_padding(&mut self, padding: u32) {
+    let old_padding = self.padding;
+    self.padding = padding;
+    self.padding_override = Some(padding);
+    self.header.edit_entry(old_padding, self.padding);
+  }
+
+  pub fn get(&self, key: &Keys) -> Option<&DataField> {
+    let idx = self.get_idx(key).ok()?;
+    self.entries_mapping.field_by_idx(idx)
+  }
+
+  pub fn get_entry<'a>(&'a self, key: &Keys) -> Option<SfoEntry<'a>> {
+    let idx = self.get_idx(key).ok()?;
+    let data = self.entries_mapping.field_by_idx(idx)?;
+    let index_table_entry = self.index_table.entries.get(idx)?;
+    Some(SfoEntry {
+      data,
+      index_table_entry,
+    })
+  }
+
+  /// Size in bytes of the file produced by `export`.
+  pub fn file_size(&self) -> u32 {
+    self.header.data_table_start
+      + self
+        .index_table
+        .entries
+        .iter()
+        .map(|entry| entry.data_max_len)
+        .sum::<u32>()
+  }
+
   fn get_idx(&self, key: &Keys) -> Result<usize, String> {
     self
       .entries_mapping
@@ -141,9 +342,15 @@ impl Sfo {
   }
 
   fn recalculate_padding(&mut self) {
+    if let Some(padding) = self.padding_override {
+      self.padding = padding;
+      return;
+    }
+
     let sum_of_keys = self.entries_mapping.keys_len();
-    self.padding =
-      KEY_TABLE_PADDING_ALIGNMENT_BYTES - (sum_of_keys as u32 % KEY_TABLE_PADDING_ALIGNMENT_BYTES)
+    self.padding = (KEY_TABLE_PADDING_ALIGNMENT_BYTES
+      - (sum_of_keys as u32 % KEY_TABLE_PADDING_ALIGNMENT_BYTES))
+      % KEY_TABLE_PADDING_ALIGNMENT_BYTES
   }
 
   pub fn iter<'a>(&'a self) -> SfoEntryIter<'a> {
@@ -186,3 +393,258 @@ impl<'a> Iterator for SfoEntryIter<'a> {
     Some((key, entry))
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use super::*;
+  use crate::sfo::{
+    format::Format,
+    test_fixtures::{exported, sample_sfo},
+    validation::validate,
+  };
+
+  /// Checks offsets of the header and index table and that the exported file reads back the same.
+  fn assert_layout(sfo: &Sfo) {
+    let issues: Vec<String> = validate(sfo)
+      .into_iter()
+      .map(|issue| issue.message)
+      .collect();
+    assert!(issues.is_empty(), "{issues:?}");
+
+    let keys_len = sfo.entries_mapping.keys_len() as u32;
+    assert_eq!(
+      sfo.header.data_table_start,
+      sfo.header.key_table_start + keys_len + sfo.padding
+    );
+
+    let bytes = exported(sfo);
+    let reread = Sfo::new(&mut Cursor::new(&bytes)).unwrap();
+    assert_eq!(exported(&reread), bytes);
+  }
+
+  #[test]
+  fn rename_keeps_data_and_reserved_size() {
+    let mut sfo = sample_sfo();
+    sfo.rename(&Keys::Title, Keys::SubTitle).unwrap();
+
+    assert!(sfo.get(&Keys::Title).is_none());
+    let (_, entry) = sfo.iter().find(|(key, _)| **key == Keys::SubTitle).unwrap();
+    assert_eq!(entry.data.to_string(), "Game");
+    assert!(matches!(entry.index_table_entry.data_format, Format::Utf8));
+    assert_eq!(entry.index_table_entry.data_len, 5);
+    assert_eq!(entry.index_table_entry.data_max_len, 128);
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn rename_reorders_keys_and_recalculates_padding() {
+    let mut sfo = sample_sfo();
+    sfo.rename(&Keys::Attribute, Keys::XmbApps).unwrap();
+    let keys: Vec<String> = sfo.iter().map(|(key, _)| key.to_string()).collect();
+    assert_eq!(keys, ["TITLE", "TITLE_ID", "XMB_APPS"]);
+    assert_layout(&sfo);
+
+    sfo.rename(&Keys::Title, Keys::Title00).unwrap();
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn rename_to_existing_key_fails() {
+    let mut sfo = sample_sfo();
+    assert!(sfo.rename(&Keys::Title, Keys::TitleId).is_err());
+    assert!(sfo.rename(&Keys::Detail, Keys::SubTitle).is_err());
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn rename_and_edit_keeps_sfo_when_edit_fails() {
+    let mut sfo = sample_sfo();
+    let before = exported(&sfo);
+
+    assert!(
+      sfo
+        .rename_and_edit(&Keys::Title, Keys::SubTitle, DataField::U32(1))
+        .is_err()
+    );
+
+    assert_eq!(exported(&sfo), before);
+    assert!(sfo.get(&Keys::SubTitle).is_none());
+  }
+
+  #[test]
+  fn rename_and_edit_applies_both_changes() {
+    let mut sfo = sample_sfo();
+    sfo
+      .rename_and_edit(
+        &Keys::Title,
+        Keys::SubTitle,
+        DataField::Utf8String(String::from("Other")),
+      )
+      .unwrap();
+
+    assert!(sfo.get(&Keys::Title).is_none());
+    assert_eq!(sfo.get(&Keys::SubTitle).unwrap().to_string(), "Other");
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn converted_switches_data_type() {
+    assert!(matches!(
+      DataField::U32(42).converted(),
+      Ok(DataField::Utf8String(text)) if text == "42"
+    ));
+    assert!(matches!(
+      DataField::Utf8String(String::from(" 42 ")).converted(),
+      Ok(DataField::U32(42))
+    ));
+    assert!(
+      DataField::Utf8String(String::from("Game"))
+        .converted()
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn convert_switches_format_of_entry() {
+    let key = Keys::Unknown(String::from("VALUE"));
+    let mut sfo = sample_sfo();
+    sfo.add(key.clone(), DataField::U32(42));
+
+    assert!(matches!(
+      sfo.convert_preview(&key),
+      Ok(DataField::Utf8String(text)) if text == "42"
+    ));
+    sfo.convert(&key).unwrap();
+    let entry = sfo.get_entry(&key).unwrap();
+    assert!(matches!(entry.index_table_entry.data_format, Format::Utf8));
+    assert_eq!(entry.data.to_string(), "42");
+    assert_layout(&sfo);
+
+    sfo.convert(&key).unwrap();
+    let entry = sfo.get_entry(&key).unwrap();
+    assert!(matches!(entry.index_table_entry.data_format, Format::U32));
+    assert!(matches!(entry.data, DataField::U32(42)));
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn convert_rejects_invalid_values_and_known_formats() {
+    let mut sfo = sample_sfo();
+    assert!(sfo.convert(&Keys::Title).is_err());
+    assert!(sfo.convert_preview(&Keys::Attribute).is_err());
+    assert!(sfo.convert(&Keys::Attribute).is_err());
+
+    assert!(matches!(
+      sfo
+        .get_entry(&Keys::Attribute)
+        .unwrap()
+        .index_table_entry
+        .data_format,
+      Format::U32
+    ));
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn add_with_layout_keeps_format_and_reserved_size() {
+    let mut sfo = sample_sfo();
+    sfo
+      .add_with_layout(
+        Keys::Category,
+        DataField::Utf8String(String::from("DG")),
+        Format::Utf8Special,
+        4,
+      )
+      .unwrap();
+
+    let entry = sfo.get_entry(&Keys::Category).unwrap();
+    assert_eq!(entry.index_table_entry.data_format, Format::Utf8Special);
+    assert_eq!(entry.index_table_entry.data_len, 2);
+    assert_eq!(entry.index_table_entry.data_max_len, 4);
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn add_with_layout_rejects_invalid_entries() {
+    let mut sfo = sample_sfo();
+    let text = DataField::Utf8String(String::from("Description"));
+    assert!(
+      sfo
+        .add_with_layout(Keys::Title, text.clone(), Format::Utf8, 128)
+        .is_err()
+    );
+    assert!(
+      sfo
+        .add_with_layout(Keys::Detail, text.clone(), Format::U32, 4)
+        .is_err()
+    );
+    assert!(
+      sfo
+        .add_with_layout(Keys::Detail, text, Format::Utf8, 8)
+        .is_err()
+    );
+    assert!(sfo.get(&Keys::Detail).is_none());
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn resize_shifts_following_data_offsets() {
+    let mut sfo = sample_sfo();
+    let title_id_offset = sfo
+      .get_entry(&Keys::TitleId)
+      .unwrap()
+      .index_table_entry
+      .data_offset;
+    sfo.resize(&Keys::Title, 256).unwrap();
+
+    let entry = sfo.get_entry(&Keys::Title).unwrap();
+    assert_eq!(entry.index_table_entry.data_max_len, 256);
+    let entry = sfo.get_entry(&Keys::TitleId).unwrap();
+    assert_eq!(entry.index_table_entry.data_offset, title_id_offset + 128);
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn resize_below_data_length_fails() {
+    let mut sfo = sample_sfo();
+    assert!(sfo.resize(&Keys::TitleId, 4).is_err());
+    assert_eq!(
+      sfo
+        .get_entry(&Keys::TitleId)
+        .unwrap()
+        .index_table_entry
+        .data_max_len,
+      16
+    );
+    assert_layout(&sfo);
+  }
+
+  #[test]
+  fn padding_override_is_kept_through_changes() {
+    let mut sfo = sample_sfo();
+    sfo.set_padding(sfo.padding + 8);
+    let padding = sfo.padding;
+
+    sfo.add(Keys::Detail, DataField::Utf8String(String::from("Text")));
+    sfo.rename(&Keys::Detail, Keys::SubTitle).unwrap();
+    sfo.delete(&Keys::Attribute).unwrap();
+    assert_eq!(sfo.padding, padding);
+
+    let keys_len = sfo.entries_mapping.keys_len() as u32;
+    assert_eq!(
+      sfo.header.data_table_start,
+      sfo.header.key_table_start + keys_len + padding
+    );
+  }
+
+  #[test]
+  fn padding_is_aligned_without_override() {
+    let mut sfo = sample_sfo();
+    sfo.add(Keys::Detail, DataField::Utf8String(String::from("Text")));
+    assert_layout(&sfo);
+    sfo.delete(&Keys::Attribute).unwrap();
+    assert_layout(&sfo);
+  }
+}
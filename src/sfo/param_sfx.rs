@@ -0,0 +1,343 @@
+use std::{
+  io::{Read, Write},
+  str::FromStr,
+};
+
+use quick_xml::{
+  Reader, Writer,
+  escape::resolve_predefined_entity,
+  events::{BytesDecl, BytesStart, BytesText, Event},
+};
+
+use crate::sfo::{Sfo, format::Format, header::PsfVersion, keys::Keys, mapping::DataField};
+
+const ROOT_TAG: &str = "paramsfo";
+const PARAM_TAG: &str = "param";
+const U32_MAX_LEN: u32 = 4;
+
+/// Writes the sfo as param.sfx XML used by the PS3 SDK and homebrew toolchains. Format and
+/// reserved size of every entry are kept in the `fmt` and `max_len` attributes.
+pub fn export_param_sfx<T>(sfo: &Sfo, writer: &mut T) -> Result<(), String>
+where
+  T: Write,
+{
+  let mut xml_writer = Writer::new_with_indent(writer, b' ', 2);
+  xml_writer
+    .write_event(Event::Decl(BytesDecl::new(
+      "1.0",
+      Some("utf-8"),
+      Some("yes"),
+    )))
+    .and_then(|_| xml_writer.write_event(Event::Start(BytesStart::new(ROOT_TAG))))
+    .map_err(|err| format!("could not write {ROOT_TAG} element: {err}"))?;
+
+  for (key, entry) in sfo.iter() {
+    let key_name = key.to_string();
+    let data_format = entry.index_table_entry.data_format;
+    let data_max_len = entry.index_table_entry.data_max_len.to_string();
+    let mut attributes = vec![
+      ("key", key_name.as_str()),
+      ("fmt", data_format.short_name()),
+    ];
+    if data_format != Format::U32 || entry.index_table_entry.data_max_len != U32_MAX_LEN {
+      attributes.push(("max_len", data_max_len.as_str()));
+    }
+
+    let value = entry.data.to_string();
+    xml_writer
+      .create_element(PARAM_TAG)
+      .with_attributes(attributes)
+      .write_text_content(BytesText::new(&value))
+      .map_err(|err| format!("could not write {PARAM_TAG} element of key {key}: {err}"))?;
+  }
+
+  xml_writer
+    .write_event(Event::End(BytesStart::new(ROOT_TAG).to_end()))
+    .map_err(|err| format!("could not write {ROOT_TAG} element: {err}"))?;
+  xml_writer
+    .get_mut()
+    .write_all(b"\n")
+    .map_err(|err| format!("could not write {ROOT_TAG} element: {err}"))
+}
+
+/// Reads param.sfx XML into a sfo in the PS3 version of the format. When `fmt` is missing, the
+/// format known for the key is used, and when `max_len` is missing, the size reserved by the
+/// console for the key, or the length of the value when it does not fit.
+pub fn import_param_sfx<T>(reader: &mut T) -> Result<Sfo, String>
+where
+  T: Read,
+{
+  let mut xml = String::new();
+  reader
+    .read_to_string(&mut xml)
+    .map_err(|err| format!("could not read param.sfx: {err}"))?;
+
+  let mut xml_reader = Reader::from_str(&xml);
+  let mut sfo = Sfo::empty(PsfVersion::V1_1);
+  let mut in_root = false;
+  let mut param: Option<Param> = None;
+
+  loop {
+    let position = xml_reader.buffer_position() as usize;
+    let line = line_at(&xml, position);
+    let event = xml_reader.read_event().map_err(|err| {
+      let line = line_at(&xml, xml_reader.error_position() as usize);
+      format!("line {line}: could not parse param.sfx: {err}")
+    })?;
+
+    match &event {
+      Event::Start(element) | Event::Empty(element)
+        if element.name().as_ref() == ROOT_TAG.as_bytes() =>
+      {
+        if in_root {
+          return Err(format!("line {line}: nested {ROOT_TAG} element"));
+        }
+        in_root = matches!(event, Event::Start(_));
+      }
+      Event::Start(element) | Event::Empty(element)
+        if element.name().as_ref() == PARAM_TAG.as_bytes() =>
+      {
+        if !in_root || param.is_some() {
+          return Err(format!(
+            "line {line}: {PARAM_TAG} element has to be placed directly in {ROOT_TAG} element"
+          ));
+        }
+
+        let new_param = Param::new(element, line)?;
+        match matches!(event, Event::Empty(_)) {
+          true => new_param.add_to(&mut sfo)?,
+          false => param = Some(new_param),
+        }
+      }
+      Event::Start(element) | Event::Empty(element) => {
+        return Err(format!(
+          "line {line}: unexpected element {}",
+          String::from_utf8_lossy(element.name().as_ref())
+        ));
+      }
+      Event::End(element) if element.name().as_ref() == PARAM_TAG.as_bytes() => {
+        if let Some(param) = param.take() {
+          param.add_to(&mut sfo)?;
+        }
+      }
+      Event::End(element) if element.name().as_ref() == ROOT_TAG.as_bytes() => {
+        in_root = false;
+      }
+      Event::Text(text) => {
+        let text = text
+          .decode()
+          .map_err(|err| format!("line {line}: could not decode text: {err}"))?;
+        match &mut param {
+          Some(param) => param.value.push_str(&text),
+          None if text.trim().is_empty() => {}
+          None => {
+            return Err(format!(
+              "line {line}: text \"{}\" outside of {PARAM_TAG} element",
+              text.trim()
+            ));
+          }
+        }
+      }
+      Event::CData(text) => {
+        let text = text
+          .decode()
+          .map_err(|err| format!("line {line}: could not decode text: {err}"))?;
+        if let Some(param) = &mut param {
+          param.value.push_str(&text);
+        }
+      }
+      Event::GeneralRef(reference) => {
+        let name = reference
+          .decode()
+          .map_err(|err| format!("line {line}: could not decode reference: {err}"))?;
+        let resolved = match reference.resolve_char_ref() {
+          Ok(Some(ch)) => ch.to_string(),
+          Ok(None) => resolve_predefined_entity(&name)
+            .map(String::from)
+            .ok_or_else(|| format!("line {line}: unknown entity &{name};"))?,
+          Err(err) => return Err(format!("line {line}: invalid reference &{name};: {err}")),
+        };
+        if let Some(param) = &mut param {
+          param.value.push_str(&resolved);
+        }
+      }
+      Event::Eof => break,
+      _ => {}
+    }
+  }
+
+  if let Some(param) = param {
+    return Err(format!(
+      "line {}: {PARAM_TAG} element of key {} is not closed",
+      param.line, param.key
+    ));
+  }
+
+  Ok(sfo)
+}
+
+struct Param {
+  line: usize,
+  key: Keys,
+  format: Option<Format>,
+  max_len: Option<u32>,
+  value: String,
+}
+
+impl Param {
+  fn new(element: &BytesStart, line: usize) -> Result<Self, String> {
+    let mut key = None;
+    let mut format = None;
+    let mut max_len = None;
+
+    for attribute in element.attributes() {
+      let attribute =
+        attribute.map_err(|err| format!("line {line}: could not read attribute: {err}"))?;
+      let value = attribute
+        .unescape_value()
+        .map_err(|err| format!("line {line}: could not read attribute value: {err}"))?;
+      match attribute.key.as_ref() {
+        b"key" => {
+          key = Some(Keys::from_str(&value).unwrap_or_else(|_| Keys::Unknown(value.to_string())))
+        }
+        b"fmt" => {
+          format = Some(Format::from_short_name(&value).ok_or_else(|| {
+            format!("line {line}: unknown fmt \"{value}\", expected utf8, utf8-s or int32")
+          })?)
+        }
+        b"max_len" => {
+          max_len = Some(value.trim().parse::<u32>().map_err(|err| {
+            format!("line {line}: max_len \"{value}\" is not an unsigned 32-bit integer: {err}")
+          })?)
+        }
+        _ => {}
+      }
+    }
+
+    let key = key.ok_or_else(|| format!("line {line}: {PARAM_TAG} element is missing key"))?;
+    Ok(Param {
+      line,
+      key,
+      format,
+      max_len,
+      value: String::new(),
+    })
+  }
+
+  fn add_to(self, sfo: &mut Sfo) -> Result<(), String> {
+    let Param {
+      line,
+      key,
+      format,
+      max_len,
+      value,
+    } = self;
+    let format = format.or_else(|| key.format()).unwrap_or(Format::Utf8);
+    let data_field = match format {
+      Format::U32 => {
+        DataField::U32(parse_u32(&value).map_err(|err| format!("line {line}: {err}"))?)
+      }
+      Format::Utf8 | Format::Utf8Special => DataField::Utf8String(value),
+    };
+
+    let max_len = max_len.unwrap_or_else(|| key.default_max_len(&data_field, format));
+
+    sfo
+      .add_with_layout(key, data_field, format, max_len)
+      .map_err(|err| format!("line {line}: {err}"))
+  }
+}
+
+/// Parses a decimal or a `0x` prefixed hexadecimal number.
+fn parse_u32(value: &str) -> Result<u32, String> {
+  let trimmed = value.trim();
+  match trimmed
+    .strip_prefix("0x")
+    .or_else(|| trimmed.strip_prefix("0X"))
+  {
+    Some(hex) => u32::from_str_radix(hex, 16),
+    None => trimmed.parse::<u32>(),
+  }
+  .map_err(|err| format!("value \"{value}\" is not an unsigned 32-bit integer: {err}"))
+}
+
+/// Number of the line, counted from 1, holding the byte at the position of the text.
+pub fn line_at(text: &str, position: usize) -> usize {
+  text.as_bytes()[..position.min(text.len())]
+    .iter()
+    .filter(|byte| **byte == b'\n')
+    .count()
+    + 1
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use super::*;
+  use crate::sfo::test_fixtures::{exported, sample_sfo};
+
+  fn sfo_with_markup() -> Sfo {
+    let mut sfo = sample_sfo();
+    sfo
+      .edit(
+        &Keys::Title,
+        DataField::Utf8String(String::from("Tom & Jerry <Deluxe>")),
+      )
+      .unwrap();
+    sfo
+      .add_with_layout(
+        Keys::Category,
+        DataField::Utf8String(String::from("HG")),
+        Format::Utf8Special,
+        4,
+      )
+      .unwrap();
+    sfo
+  }
+
+  fn import(xml: &str) -> Result<Sfo, String> {
+    import_param_sfx(&mut Cursor::new(xml))
+  }
+
+  fn import_err(xml: &str) -> String {
+    import(xml).err().expect("import should fail")
+  }
+
+  #[test]
+  fn round_trip_keeps_values_formats_and_sizes() {
+    let sfo = sfo_with_markup();
+    let mut xml = Vec::new();
+    export_param_sfx(&sfo, &mut xml).unwrap();
+    let xml = String::from_utf8(xml).unwrap();
+
+    assert!(xml.contains("Tom &amp; Jerry &lt;Deluxe&gt;"), "{xml}");
+    assert_eq!(exported(&import(&xml).unwrap()), exported(&sfo));
+  }
+
+  #[test]
+  fn import_fills_missing_format_and_size_from_the_key() {
+    let sfo = import(
+      "<paramsfo>\n  <param key=\"TITLE\">Game</param>\n  <param key=\"ATTRIBUTE\">0x20</param>\n</paramsfo>",
+    )
+    .unwrap();
+
+    let title = sfo.get_entry(&Keys::Title).unwrap();
+    assert_eq!(title.index_table_entry.data_format, Format::Utf8);
+    assert_eq!(title.index_table_entry.data_max_len, 128);
+    let attribute = sfo.get_entry(&Keys::Attribute).unwrap();
+    assert!(matches!(attribute.data, DataField::U32(0x20)));
+  }
+
+  #[test]
+  fn import_reports_the_line_of_the_failing_element() {
+    let err = import_err(
+      "<paramsfo>\n  <param key=\"TITLE\">Game</param>\n  <param key=\"ATTRIBUTE\">x</param>\n</paramsfo>",
+    );
+    assert!(err.starts_with("line 3:"), "{err}");
+
+    let err =
+      import_err("<paramsfo>\n  <param key=\"TITLE\" fmt=\"text\">Game</param>\n</paramsfo>");
+    assert!(err.starts_with("line 2:"), "{err}");
+  }
+}
@@ -0,0 +1,94 @@
+//! Builders of sfo files shared by unit tests.
+
+use std::io::Cursor;
+
+use crate::sfo::{Sfo, format::Format, header::PsfVersion, keys::Keys, mapping::DataField};
+
+/// Entry of a file built by `psf_bytes`.
+pub struct PsfEntry<'a> {
+  pub key: &'a str,
+  pub format: Format,
+  /// Stored bytes, including the null terminator of utf8 values.
+  pub data: &'a [u8],
+  pub max_len: u32,
+}
+
+/// Bytes of a PSF 1.1 file holding the entries, which have to be sorted by key. The key table is
+/// padded to 4 bytes and data of every entry is padded with zeroes to its reserved size.
+pub fn psf_bytes(entries: &[PsfEntry]) -> Vec<u8> {
+  let keys_len: u32 = entries.iter().map(|entry| entry.key.len() as u32 + 1).sum();
+  let key_table_start = 20 + 16 * entries.len() as u32;
+  let data_table_start = key_table_start + keys_len.next_multiple_of(4);
+
+  let mut bytes = vec![0x00, 0x50, 0x53, 0x46];
+  for field in [
+    0x0101,
+    key_table_start,
+    data_table_start,
+    entries.len() as u32,
+  ] {
+    bytes.extend(field.to_le_bytes());
+  }
+
+  let (mut key_offset, mut data_offset) = (0u16, 0u32);
+  for entry in entries {
+    bytes.extend(key_offset.to_le_bytes());
+    bytes.extend(<[u8; 2]>::from(entry.format));
+    bytes.extend((entry.data.len() as u32).to_le_bytes());
+    bytes.extend(entry.max_len.to_le_bytes());
+    bytes.extend(data_offset.to_le_bytes());
+    key_offset += entry.key.len() as u16 + 1;
+    data_offset += entry.max_len;
+  }
+
+  for entry in entries {
+    bytes.extend(entry.key.as_bytes());
+    bytes.push(0);
+  }
+  bytes.resize(data_table_start as usize, 0);
+
+  for entry in entries {
+    let start = bytes.len();
+    bytes.extend(entry.data);
+    bytes.resize(start + entry.max_len as usize, 0);
+  }
+
+  bytes
+}
+
+/// Sfo read from a file holding a number, a text and a text with reserved space after the value.
+pub fn sample_sfo() -> Sfo {
+  let bytes = psf_bytes(&[
+    PsfEntry {
+      key: "ATTRIBUTE",
+      format: Format::U32,
+      data: &0x20u32.to_le_bytes(),
+      max_len: 4,
+    },
+    PsfEntry {
+      key: "TITLE",
+      format: Format::Utf8,
+      data: b"Game\0",
+      max_len: 128,
+    },
+    PsfEntry {
+      key: "TITLE_ID",
+      format: Format::Utf8,
+      data: b"BLUS12345\0",
+      max_len: 16,
+    },
+  ]);
+  Sfo::new(&mut Cursor::new(bytes)).unwrap()
+}
+
+pub fn sfo_with_title(title: &str) -> Sfo {
+  let mut sfo = Sfo::empty(PsfVersion::V1_1);
+  sfo.add(Keys::Title, DataField::Utf8String(String::from(title)));
+  sfo
+}
+
+pub fn exported(sfo: &Sfo) -> Vec<u8> {
+  let mut bytes = Vec::new();
+  sfo.export(&mut bytes).unwrap();
+  bytes
+}
@@ -0,0 +1,245 @@
+use std::fmt::Display;
+
+use crate::sfo::{
+  KEY_TABLE_PADDING_ALIGNMENT_BYTES, Sfo, SfoEntry,
+  format::Format,
+  header::{HEADER_SIZE, KEY_TABLE_ENTRY_SIZE},
+  keys::Keys,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+  Error,
+  /// Unusual but possibly correct content, such as a key not expected in the version of the file.
+  Warning,
+}
+
+pub struct ValidationIssue {
+  pub key: Option<Keys>,
+  pub message: String,
+  pub severity: Severity,
+}
+
+impl ValidationIssue {
+  pub fn is_error(&self) -> bool {
+    self.severity == Severity::Error
+  }
+}
+
+impl Display for ValidationIssue {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.severity {
+      Severity::Error => write!(f, "{}", self.message),
+      Severity::Warning => write!(f, "warning: {}", self.message),
+    }
+  }
+}
+
+/// Checks entries against known key limits and formats, and the header against the layout of
+/// index, key and data tables. Keys and versions which are only unusual for the version of the
+/// file are reported as warnings.
+pub fn validate(sfo: &Sfo) -> Vec<ValidationIssue> {
+  let psf_version = sfo.header.psf_version();
+  let mut issues: Vec<ValidationIssue> = Vec::new();
+  if psf_version.is_none() {
+    issues.push(ValidationIssue {
+      key: None,
+      message: format!(
+        "header version {:#010X} ({}) is not a known PSF version",
+        sfo.header.version,
+        sfo.header.version_name()
+      ),
+      severity: Severity::Warning,
+    });
+  }
+
+  for (key, entry) in sfo.iter() {
+    issues.extend(
+      validate_entry(key, &entry)
+        .into_iter()
+        .map(|message| ValidationIssue {
+          key: Some(key.clone()),
+          message,
+          severity: Severity::Error,
+        }),
+    );
+
+    if let Some(psf_version) = psf_version
+      && key.min_version() > psf_version
+    {
+      issues.push(ValidationIssue {
+        key: Some(key.clone()),
+        message: format!(
+          "key {key} is not expected before PSF version {}, file has version {psf_version}",
+          key.min_version()
+        ),
+        severity: Severity::Warning,
+      });
+    }
+  }
+
+  issues.extend(
+    validate_layout(sfo)
+      .into_iter()
+      .map(|message| ValidationIssue {
+        key: None,
+        message,
+        severity: Severity::Error,
+      }),
+  );
+  issues
+}
+
+pub fn validate_entry(key: &Keys, entry: &SfoEntry) -> Vec<String> {
+  let mut issues = Vec::new();
+  let index_table_entry = entry.index_table_entry;
+
+  if let Some(format) = key.format()
+    && !format.accepts(entry.data)
+  {
+    issues.push(format!(
+      "key {key} should hold values of format \"{format}\", found \"{}\"",
+      index_table_entry.data_format
+    ));
+  }
+
+  if index_table_entry.data_len > index_table_entry.data_max_len {
+    issues.push(format!(
+      "data length of {} bytes exceeds reserved size of {} bytes",
+      index_table_entry.data_len, index_table_entry.data_max_len
+    ));
+  }
+
+  if let Some(max_len) = key.max_len()
+    && index_table_entry.data_len > max_len
+  {
+    issues.push(format!(
+      "data length of {} bytes exceeds maximum of {max_len} bytes for key {key}",
+      index_table_entry.data_len
+    ));
+  }
+
+  if let Format::U32 = index_table_entry.data_format
+    && (index_table_entry.data_len != 4 || index_table_entry.data_max_len != 4)
+  {
+    issues.push(String::from(
+      "unsigned 32-bit integer entry should have data length and reserved size of 4 bytes",
+    ));
+  }
+
+  issues
+}
+
+fn validate_layout(sfo: &Sfo) -> Vec<String> {
+  let mut issues = Vec::new();
+  let entries_count = sfo.index_table.entries.len() as u32;
+
+  if sfo.header.table_entries != entries_count {
+    issues.push(format!(
+      "header declares {} entries while index table holds {entries_count}",
+      sfo.header.table_entries
+    ));
+  }
+
+  let key_table_start = HEADER_SIZE + entries_count * KEY_TABLE_ENTRY_SIZE as u32;
+  if sfo.header.key_table_start != key_table_start {
+    issues.push(format!(
+      "key table start offset {:#X} does not match expected {key_table_start:#X}",
+      sfo.header.key_table_start
+    ));
+  }
+
+  let data_table_start =
+    sfo.header.key_table_start + sfo.entries_mapping.keys_len() as u32 + sfo.padding;
+  if sfo.header.data_table_start != data_table_start {
+    issues.push(format!(
+      "data table start offset {:#X} does not match expected {data_table_start:#X}",
+      sfo.header.data_table_start
+    ));
+  }
+
+  if !sfo
+    .header
+    .data_table_start
+    .is_multiple_of(KEY_TABLE_PADDING_ALIGNMENT_BYTES)
+  {
+    issues.push(format!(
+      "data table start offset {:#X} is not aligned to {KEY_TABLE_PADDING_ALIGNMENT_BYTES} bytes",
+      sfo.header.data_table_start
+    ));
+  }
+
+  let mut key_offset: u32 = 0;
+  let mut data_offset: u32 = 0;
+  for (key, entry) in sfo.iter() {
+    if entry.index_table_entry.key_offset as u32 != key_offset {
+      issues.push(format!(
+        "key offset {:#X} of key {key} does not match expected {key_offset:#X}",
+        entry.index_table_entry.key_offset
+      ));
+    }
+
+    if entry.index_table_entry.data_offset != data_offset {
+      issues.push(format!(
+        "data offset {:#X} of key {key} does not match expected {data_offset:#X}",
+        entry.index_table_entry.data_offset
+      ));
+    }
+
+    key_offset += key.len() as u32;
+    data_offset += entry.index_table_entry.data_max_len;
+  }
+
+  issues
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sfo::{header::PsfVersion, mapping::DataField, test_fixtures::sample_sfo};
+
+  fn sfo_with_xmb_apps() -> Sfo {
+    let mut sfo = sample_sfo();
+    sfo.add(Keys::XmbApps, DataField::U32(1));
+    sfo
+  }
+
+  #[test]
+  fn valid_sfo_has_no_issues() {
+    assert!(validate(&sfo_with_xmb_apps()).is_empty());
+  }
+
+  #[test]
+  fn key_newer_than_header_version_is_a_warning() {
+    let mut sfo = sfo_with_xmb_apps();
+    sfo.header.version = PsfVersion::V1_0.raw();
+
+    let issues = validate(&sfo);
+    assert_eq!(issues.len(), 1);
+    assert!(!issues[0].is_error());
+    assert!(matches!(&issues[0].key, Some(Keys::XmbApps)));
+    assert_eq!(
+      issues[0].to_string(),
+      "warning: key XMB_APPS is not expected before PSF version 1.1, file has version 1.0"
+    );
+  }
+
+  #[test]
+  fn misaligned_data_table_start_is_an_error() {
+    let mut sfo = sfo_with_xmb_apps();
+    sfo.header.data_table_start += 2;
+
+    let messages: Vec<String> = validate(&sfo)
+      .into_iter()
+      .inspect(|issue| assert!(issue.is_error() && issue.key.is_none()))
+      .map(|issue| issue.message)
+      .collect();
+    assert_eq!(
+      messages,
+      [
+        "data table start offset 0x7A does not match expected 0x78",
+        "data table start offset 0x7A is not aligned to 4 bytes",
+      ]
+    );
+  }
+}